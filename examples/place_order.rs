@@ -0,0 +1,74 @@
+use dotenv::dotenv;
+use ethers::signers::LocalWallet;
+use hyperliquid::http::client::HttpClient;
+use hyperliquid::models::{
+    CancelRequest, Cloid, MarketType, OrderGrouping, OrderRequest, OrderResponseStatus, OrderType,
+    Tif, TokenManager,
+};
+use log::{error, info};
+use std::env;
+use std::str::FromStr;
+
+fn wallet_from_env() -> LocalWallet {
+    let private_key = env::var("PRIVATE_KEY").expect("PRIVATE_KEY not set");
+    LocalWallet::from_str(&private_key).expect("Invalid private key")
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok(); //Load environment variables from .env file
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    // テストネットで実行
+    let client = HttpClient::new_with_wallet(true, wallet_from_env());
+    let token_manager = TokenManager::from_api(&client).await.unwrap();
+
+    let asset = match token_manager.get_token_index(MarketType::Perp, "ETH") {
+        Some(index) => *index,
+        None => {
+            error!("Token not found");
+            return;
+        }
+    };
+
+    // 約定しない価格で指値注文
+    let mut order = OrderRequest::new(
+        asset,
+        true,
+        1000.0,
+        0.01,
+        false,
+        OrderType::Limit { tif: Tif::Gtc },
+    );
+    order.cloid = Some(Cloid::random());
+
+    info!("Placing order...");
+    let statuses = match client
+        .place_order(vec![order], OrderGrouping::Na, None)
+        .await
+    {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            error!("Failed to place order: {}", e);
+            return;
+        }
+    };
+    info!("Order statuses: {:#?}", statuses);
+
+    // 板に残った注文をキャンセル
+    let cancels: Vec<CancelRequest> = statuses
+        .iter()
+        .filter_map(|status| match status {
+            OrderResponseStatus::Resting(resting) => {
+                Some(CancelRequest::new(asset, resting.order_id))
+            }
+            _ => None,
+        })
+        .collect();
+
+    info!("Canceling orders...");
+    match client.cancel_order(cancels).await {
+        Ok(statuses) => info!("Cancel statuses: {:#?}", statuses),
+        Err(e) => error!("Failed to cancel orders: {}", e),
+    }
+}
@@ -1,15 +1,34 @@
 use crate::models::{
-    CandleSnapshotRequest, CandleSnapshotResponse, HistoricalOrdersResponse, L2BookRequest,
-    L2BookResponse, OpenOrdersResponse, OrderStatusRequest, OrderStatusResponse, PerpMetaResponse,
-    RateLimitResponse, SpotAssetResponse, SpotMetaResponse, SpotTokenBalancesResponse,
-    UserFillsResponse,
+    Action, ApproveAgent, ApproveBuilderFee, BuilderInfo, BulkCancel, BulkCancelByCloid,
+    BulkModify, BulkOrder, CancelByCloidRequest, CancelRequest, CancelResponseStatus,
+    CancelStatuses, CandleSnapshotRequest, CandleSnapshotResponse, Cloid, CreateSubAccount,
+    ExchangeRequest, ExchangeResponse, ExchangeResponseData, FrontendOpenOrdersResponse,
+    FundingHistoryResponse, HistoricalOrderFilter, HistoricalOrdersResponse, L2BookRequest,
+    L2BookResponse, ModifyRequest, OpenOrder, OpenOrdersResponse, OrderGrouping, OrderRequest,
+    OrderResponseStatus, OrderStatusRequest, OrderStatusResponse, OrderStatuses,
+    PerpAccountSummaryResponse, PerpAsset, PerpAssetContextsResponse, PerpMetaResponse,
+    PredictedFunding, PredictedFundingsResponse, RateLimitResponse, SpotAssetResponse,
+    SpotDeployAuction, SpotDeployStateResponse, SpotMetaResponse, SpotSend,
+    SpotTokenBalancesResponse, SubAccountSpotTransfer, SubAccountTransfer, SubAccountsResponse,
+    Token, TokenManager, TwapCancel, TwapCancelResponse, TwapOrder, TwapOrderResponse,
+    TwapOrderStatus, TwapRequest, TwapSliceFillsResponse, UpdateIsolatedMargin, UpdateLeverage,
+    UsdClassTransfer, UsdSend, UserFills, UserFillsResponse, UserFundingResponse, UserSignedAction,
+    UserVaultEquitiesResponse, VaultDetailsResponse, VaultTransfer, VenueFundingRate, Withdraw3,
 };
+use crate::signer::{
+    hyperliquid_chain, sign_l1_action, sign_user_signed_action, SIGNATURE_CHAIN_ID,
+};
+use crate::utils::data_conversion::{float_to_int, float_to_wire};
+use crate::utils::time::current_epoch_millis;
+use ethers::signers::LocalWallet;
 use ethers::types::H160;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use log::debug;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// HttpClientError 型を定義
 #[derive(Debug)]
@@ -17,6 +36,10 @@ pub enum HttpClientError {
     RequestFailed(reqwest::Error),
     JsonParse(String),
     InvalidInput(String),
+    WalletNotSet,
+    SigningFailed(String),
+    ExchangeError(String),
+    IncompleteData(String),
 }
 
 impl std::fmt::Display for HttpClientError {
@@ -25,6 +48,10 @@ impl std::fmt::Display for HttpClientError {
             HttpClientError::RequestFailed(e) => write!(f, "Request failed: {}", e),
             HttpClientError::JsonParse(e) => write!(f, "Failed to parse JSON: {}", e),
             HttpClientError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            HttpClientError::WalletNotSet => write!(f, "Wallet is not set"),
+            HttpClientError::SigningFailed(e) => write!(f, "Signing failed: {}", e),
+            HttpClientError::ExchangeError(msg) => write!(f, "Exchange error: {}", msg),
+            HttpClientError::IncompleteData(msg) => write!(f, "Incomplete data: {}", msg),
         }
     }
 }
@@ -34,6 +61,11 @@ impl Error for HttpClientError {}
 pub struct HttpClient {
     client: Client,
     base_url: String,
+    is_mainnet: bool,
+    wallet: Option<LocalWallet>,
+    is_agent: bool,
+    vault_address: Option<H160>,
+    last_nonce: AtomicU64,
 }
 
 impl HttpClient {
@@ -46,6 +78,37 @@ impl HttpClient {
         HttpClient {
             client: Client::new(),
             base_url,
+            is_mainnet: !is_test,
+            wallet: None,
+            is_agent: false,
+            vault_address: None,
+            last_nonce: AtomicU64::new(0),
+        }
+    }
+
+    // 署名が必要な /exchange のリクエストに使うウォレットを設定
+    pub fn new_with_wallet(is_test: bool, wallet: LocalWallet) -> Self {
+        HttpClient {
+            wallet: Some(wallet),
+            ..Self::new(is_test)
+        }
+    }
+
+    /// APIウォレット（エージェント）で署名するクライアント
+    ///
+    /// エージェントはapprove_api_walletで承認したマスターアカウントの代わりに取引する。
+    /// vault_addressを指定した場合はそのVaultとして取引する。
+    /// 送金・出金などのユーザー署名アクションはマスターのウォレットでしか署名できない。
+    pub fn new_with_agent(
+        is_test: bool,
+        agent_wallet: LocalWallet,
+        vault_address: Option<H160>,
+    ) -> Self {
+        HttpClient {
+            wallet: Some(agent_wallet),
+            is_agent: true,
+            vault_address,
+            ..Self::new(is_test)
         }
     }
 
@@ -53,14 +116,108 @@ impl HttpClient {
         &self,
         info_request: U,
     ) -> Result<T, HttpClientError> {
-        // JSONに変換
-        let data = serde_json::to_string(&info_request)
+        self.post("/info", &info_request).await
+    }
+
+    // nonceは現在時刻（ミリ秒）だが、同一ミリ秒内の重複を避けるため単調増加させる
+    fn next_nonce(&self) -> u64 {
+        let now = current_epoch_millis();
+        let mut last = self.last_nonce.load(Ordering::SeqCst);
+        loop {
+            let nonce = now.max(last + 1);
+            match self
+                .last_nonce
+                .compare_exchange(last, nonce, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return nonce,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    fn wallet(&self) -> Result<&LocalWallet, HttpClientError> {
+        self.wallet.as_ref().ok_or(HttpClientError::WalletNotSet)
+    }
+
+    /// L1アクションに署名して /exchange に送信する
+    pub async fn send_exchange_request(
+        &self,
+        action: Action,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let nonce = self.next_nonce();
+        let signature = sign_l1_action(
+            self.wallet()?,
+            &action,
+            self.vault_address,
+            nonce,
+            self.is_mainnet,
+        )
+        .map_err(|e| HttpClientError::SigningFailed(e.to_string()))?;
+
+        let request = ExchangeRequest {
+            action,
+            nonce,
+            signature,
+            vault_address: self.vault_address,
+        };
+        self.post_exchange(&request).await
+    }
+
+    /// ユーザー署名アクション（送金・出金など）に署名して /exchange に送信する
+    pub async fn send_user_signed_request<T: UserSignedAction>(
+        &self,
+        action: T,
+        nonce: u64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        if self.is_agent {
+            return Err(HttpClientError::InvalidInput(format!(
+                "{} must be signed by the master wallet, not an agent",
+                T::PRIMARY_TYPE
+            )));
+        }
+
+        let message = action
+            .signing_message()
             .map_err(|e| HttpClientError::JsonParse(e.to_string()))?;
+        let signature =
+            sign_user_signed_action(self.wallet()?, &message, T::PRIMARY_TYPE, T::FIELDS)
+                .map_err(|e| HttpClientError::SigningFailed(e.to_string()))?;
+
+        let request = ExchangeRequest {
+            action: action.into_action(),
+            nonce,
+            signature,
+            vault_address: None,
+        };
+        self.post_exchange(&request).await
+    }
+
+    async fn post_exchange<U: Serialize>(
+        &self,
+        request: &U,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        match self
+            .post::<ExchangeResponse, U>("/exchange", request)
+            .await?
+        {
+            ExchangeResponse::Ok(data) => Ok(data),
+            ExchangeResponse::Err(msg) => Err(HttpClientError::ExchangeError(msg)),
+        }
+    }
+
+    async fn post<T: for<'de> Deserialize<'de>, U: Serialize>(
+        &self,
+        path: &str,
+        body: &U,
+    ) -> Result<T, HttpClientError> {
+        // JSONに変換
+        let data =
+            serde_json::to_string(body).map_err(|e| HttpClientError::JsonParse(e.to_string()))?;
 
         // POSTリクエストを送信
         let response = self
             .client
-            .post(format!("{}{}", self.base_url, "/info"))
+            .post(format!("{}{}", self.base_url, path))
             .header("Content-Type", "application/json")
             .body(data)
             .send()
@@ -111,12 +268,117 @@ impl HttpClient {
         self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_frontend_open_orders(&self) {
-        todo!("frontendOpenOrders");
+    pub async fn fetch_frontend_open_orders(
+        &self,
+        address: H160,
+    ) -> Result<FrontendOpenOrdersResponse, HttpClientError> {
+        let request_body = serde_json::json!({"type": "frontendOpenOrders", "user": address});
+        self.send_info_request(request_body).await
+    }
+
+    /// start_timeからend_time（省略時は現在）までの約定を全て取得する
+    ///
+    /// 1回のレスポンスの上限件数に達した場合は自動でページングする。
+    pub async fn fetch_user_fills_by_time(
+        &self,
+        address: H160,
+        start_time: u64,
+        end_time: Option<u64>,
+        aggregate_by_time: Option<bool>,
+    ) -> Result<UserFillsResponse, HttpClientError> {
+        self.user_fills_by_time_stream(address, start_time, end_time, aggregate_by_time)
+            .try_collect()
+            .await
     }
 
-    pub async fn fetch_user_fills_by_time(&self) {
-        todo!("userFillsByTime");
+    /// fetch_user_fills_by_time と同じ約定を、ページを取得するごとに1件ずつ流すStream
+    pub fn user_fills_by_time_stream(
+        &self,
+        address: H160,
+        start_time: u64,
+        end_time: Option<u64>,
+        aggregate_by_time: Option<bool>,
+    ) -> impl Stream<Item = Result<UserFills, HttpClientError>> + '_ {
+        // (次のstartTime, startTimeの時刻に取得済みのtid, 次に返すエラー, 終了フラグ)
+        let state = (start_time, HashSet::new(), None, false);
+
+        stream::unfold(
+            state,
+            move |(start_time, boundary_tids, pending_error, done)| async move {
+                if done {
+                    return None;
+                }
+                if let Some(e) = pending_error {
+                    return Some((Err(e), (start_time, boundary_tids, None, true)));
+                }
+
+                let mut request_body = serde_json::json!({
+                    "type": "userFillsByTime",
+                    "user": address,
+                    "startTime": start_time,
+                });
+                let request = request_body.as_object_mut().unwrap();
+                if let Some(end_time) = end_time {
+                    request.insert("endTime".to_string(), serde_json::json!(end_time));
+                }
+                if let Some(aggregate_by_time) = aggregate_by_time {
+                    request.insert(
+                        "aggregateByTime".to_string(),
+                        serde_json::Value::Bool(aggregate_by_time),
+                    );
+                }
+
+                let page: UserFillsResponse = match self.send_info_request(request_body).await {
+                    Ok(page) => page,
+                    Err(e) => return Some((Err(e), (start_time, boundary_tids, None, true))),
+                };
+
+                let is_last_page = page.len() < USER_FILLS_PAGE_LIMIT;
+                let next_start_time = page
+                    .iter()
+                    .map(|fill| fill.timestamp)
+                    .max()
+                    .unwrap_or(start_time);
+
+                // 次のページは最後の時刻から取り直すので、その時刻の約定のtidだけを重複除外用に残す
+                let next_boundary_tids: HashSet<u64> = page
+                    .iter()
+                    .filter(|fill| fill.timestamp == next_start_time)
+                    .map(|fill| fill.tid)
+                    .collect();
+                let fills: Vec<UserFills> = page
+                    .into_iter()
+                    .filter(|fill| !boundary_tids.contains(&fill.tid))
+                    .collect();
+
+                debug!(
+                    "userFillsByTime: start_time={}, new fills={}",
+                    start_time,
+                    fills.len()
+                );
+
+                // 同じミリ秒の約定だけでページが埋まると次のリクエストが同じ内容になり先に進めない。
+                // 残りの約定を取得できないため、取得済みの分を返した後にエラーにする
+                if !is_last_page && (next_start_time <= start_time || fills.is_empty()) {
+                    let e = HttpClientError::IncompleteData(format!(
+                        "userFillsByTime cannot advance past {}: more than {} fills at the same time",
+                        start_time, USER_FILLS_PAGE_LIMIT
+                    ));
+                    return Some((Ok(fills), (start_time, boundary_tids, Some(e), false)));
+                }
+
+                let done = is_last_page
+                    || end_time.is_some_and(|end_time| next_start_time >= end_time);
+                Some((Ok(fills), (next_start_time, next_boundary_tids, None, done)))
+            },
+        )
+        .flat_map(|page| {
+            let items: Vec<Result<UserFills, HttpClientError>> = match page {
+                Ok(fills) => fills.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        })
     }
 
     pub async fn fetch_rate_limits(
@@ -131,7 +393,7 @@ impl HttpClient {
         &self,
         address: H160,
         oid: Option<u64>,
-        cloid: Option<String>,
+        cloid: Option<Cloid>,
     ) -> Result<OrderStatusResponse, HttpClientError> {
         let request_body =
             OrderStatusRequest::new(address, oid, cloid).map_err(HttpClientError::InvalidInput)?;
@@ -159,8 +421,15 @@ impl HttpClient {
         self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_builder_fee_approval(&self) {
-        todo!("fetch_builder_fee_approval");
+    /// builderに対して承認済みの最大手数料（0.1bps単位）を取得する
+    pub async fn fetch_builder_fee_approval(
+        &self,
+        address: H160,
+        builder: H160,
+    ) -> Result<u32, HttpClientError> {
+        let request_body =
+            serde_json::json!({"type": "maxBuilderFee", "user": address, "builder": builder});
+        self.send_info_request(request_body).await
     }
 
     pub async fn fetch_historical_orders(
@@ -171,12 +440,57 @@ impl HttpClient {
         self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_twap_slice_fills(&self) {
-        todo!("fetch_twap_slice_fills");
+    pub async fn fetch_historical_orders_with_filter(
+        &self,
+        address: H160,
+        filter: &HistoricalOrderFilter,
+    ) -> Result<HistoricalOrdersResponse, HttpClientError> {
+        let orders = self.fetch_historical_orders(address).await?;
+        Ok(filter.apply(orders))
+    }
+
+    pub async fn fetch_twap_slice_fills(
+        &self,
+        address: H160,
+    ) -> Result<TwapSliceFillsResponse, HttpClientError> {
+        let request_body = serde_json::json!({"type": "userTwapSliceFills", "user": address});
+        self.send_info_request(request_body).await
+    }
+
+    pub async fn fetch_subaccounts(
+        &self,
+        address: H160,
+    ) -> Result<SubAccountsResponse, HttpClientError> {
+        let request_body = serde_json::json!({"type": "subAccounts", "user": address});
+        // サブアカウントが無い場合はnullが返る
+        let response: Option<SubAccountsResponse> = self.send_info_request(request_body).await?;
+        Ok(response.unwrap_or_default())
+    }
+
+    pub async fn fetch_vault_details(
+        &self,
+        vault_address: H160,
+        user: Option<H160>,
+    ) -> Result<VaultDetailsResponse, HttpClientError> {
+        let mut request_body =
+            serde_json::json!({"type": "vaultDetails", "vaultAddress": vault_address});
+
+        // userを指定するとfollowerStateにそのユーザーの情報が入る
+        if let Some(user) = user {
+            request_body
+                .as_object_mut()
+                .unwrap()
+                .insert("user".to_string(), serde_json::json!(user));
+        }
+        self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_subaccounts(&self) {
-        todo!("fetch_user_subaccounts");
+    pub async fn fetch_user_vault_equities(
+        &self,
+        address: H160,
+    ) -> Result<UserVaultEquitiesResponse, HttpClientError> {
+        let request_body = serde_json::json!({"type": "userVaultEquities", "user": address});
+        self.send_info_request(request_body).await
     }
 
     pub async fn fetch_spot_meta(&self) -> Result<SpotMetaResponse, HttpClientError> {
@@ -197,8 +511,14 @@ impl HttpClient {
         self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_auction_info(&self) {
-        todo!("fetch_spot_deploy_auction_info");
+    /// Spotトークンデプロイのガスオークションの状態を取得する
+    pub async fn fetch_auction_info(
+        &self,
+        address: H160,
+    ) -> Result<SpotDeployAuction, HttpClientError> {
+        let request_body = serde_json::json!({"type": "spotDeployState", "user": address});
+        let response: SpotDeployStateResponse = self.send_info_request(request_body).await?;
+        Ok(response.gas_auction)
     }
 
     pub async fn fetch_perp_meta(&self) -> Result<PerpMetaResponse, HttpClientError> {
@@ -206,91 +526,600 @@ impl HttpClient {
         self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_perpetuals_asset_contexts(&self) {
-        todo!("fetch_perpetuals_asset_contexts");
+    /// 全Perpのメタデータとファンディング・建玉・価格などのコンテキストを取得する
+    pub async fn fetch_perpetuals_asset_contexts(&self) -> Result<Vec<PerpAsset>, HttpClientError> {
+        let request_body = serde_json::json!({"type": "metaAndAssetCtxs"});
+        let (meta, contexts): PerpAssetContextsResponse =
+            self.send_info_request(request_body).await?;
+
+        if meta.universe.len() != contexts.len() {
+            return Err(HttpClientError::JsonParse(format!(
+                "Universe and asset contexts length mismatch: {} != {}",
+                meta.universe.len(),
+                contexts.len()
+            )));
+        }
+
+        Ok(meta
+            .universe
+            .into_iter()
+            .zip(contexts)
+            .map(|(universe, context)| PerpAsset { universe, context })
+            .collect())
     }
 
-    pub async fn fetch_perp_account_summary(&self) {
-        todo!("fetch_user_perpetuals_account_summary");
+    pub async fn fetch_perp_account_summary(
+        &self,
+        address: H160,
+    ) -> Result<PerpAccountSummaryResponse, HttpClientError> {
+        let request_body = serde_json::json!({"type": "clearinghouseState", "user": address});
+        self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_funding_history(&self) {
-        todo!("fetch_user_funding_history");
+    /// ユーザーのファンディングの受け払い履歴を取得する（end_timeを省略した場合は現在まで）
+    pub async fn fetch_funding_history(
+        &self,
+        address: H160,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<UserFundingResponse, HttpClientError> {
+        let mut request_body =
+            serde_json::json!({"type": "userFunding", "user": address, "startTime": start_time});
+        if let Some(end_time) = end_time {
+            request_body
+                .as_object_mut()
+                .unwrap()
+                .insert("endTime".to_string(), serde_json::json!(end_time));
+        }
+        self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_historical_funding_rates(&self) {
-        todo!("fetch_historical_funding_rates");
+    pub async fn fetch_historical_funding_rates(
+        &self,
+        coin: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<FundingHistoryResponse, HttpClientError> {
+        let mut request_body =
+            serde_json::json!({"type": "fundingHistory", "coin": coin, "startTime": start_time});
+        if let Some(end_time) = end_time {
+            request_body
+                .as_object_mut()
+                .unwrap()
+                .insert("endTime".to_string(), serde_json::json!(end_time));
+        }
+        self.send_info_request(request_body).await
     }
 
-    pub async fn fetch_funding_rate_predictions(&self) {
-        todo!("fetch_predicted_funding_rates");
+    /// 各取引所の次回ファンディングレートの予測を取得する
+    pub async fn fetch_funding_rate_predictions(
+        &self,
+    ) -> Result<Vec<PredictedFunding>, HttpClientError> {
+        let request_body = serde_json::json!({"type": "predictedFundings"});
+        let response: PredictedFundingsResponse = self.send_info_request(request_body).await?;
+
+        Ok(response
+            .into_iter()
+            .map(|(coin, venues)| PredictedFunding {
+                coin,
+                venues: venues
+                    .into_iter()
+                    .filter_map(|(venue, rate)| {
+                        rate.map(|rate| VenueFundingRate {
+                            venue,
+                            funding_rate: rate.funding_rate,
+                            next_funding_time: rate.next_funding_time,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect())
     }
 
-    pub async fn place_order(&self) {
-        todo!("place_order");
+    pub async fn place_order(
+        &self,
+        orders: Vec<OrderRequest>,
+        grouping: OrderGrouping,
+        builder: Option<BuilderInfo>,
+    ) -> Result<Vec<OrderResponseStatus>, HttpClientError> {
+        let orders = orders
+            .iter()
+            .map(OrderRequest::to_wire)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(HttpClientError::InvalidInput)?;
+
+        let response = self
+            .send_exchange_request(Action::Order(BulkOrder {
+                orders,
+                grouping,
+                builder,
+            }))
+            .await?;
+        let statuses: OrderStatuses = parse_response_data(response)?;
+        Ok(statuses.statuses)
     }
 
-    pub async fn cancel_order(&self) {
-        todo!("cancel_order");
+    pub async fn cancel_order(
+        &self,
+        cancels: Vec<CancelRequest>,
+    ) -> Result<Vec<CancelResponseStatus>, HttpClientError> {
+        let cancels = cancels.iter().map(CancelRequest::to_wire).collect();
+        let response = self
+            .send_exchange_request(Action::Cancel(BulkCancel { cancels }))
+            .await?;
+        let statuses: CancelStatuses = parse_response_data(response)?;
+        Ok(statuses.statuses)
     }
 
-    pub async fn cancel_order_by_client_order_id(&self) {
-        todo!("cancel_order_by_cloid");
+    pub async fn cancel_order_by_client_order_id(
+        &self,
+        cancels: Vec<CancelByCloidRequest>,
+    ) -> Result<Vec<CancelResponseStatus>, HttpClientError> {
+        let cancels = cancels.iter().map(CancelByCloidRequest::to_wire).collect();
+        let response = self
+            .send_exchange_request(Action::CancelByCloid(BulkCancelByCloid { cancels }))
+            .await?;
+        let statuses: CancelStatuses = parse_response_data(response)?;
+        Ok(statuses.statuses)
     }
 
-    pub async fn cancel_all_orders(&self) {
-        todo!("cancel_all_orders");
+    /// 全ての注文（coinを指定した場合はそのcoinの注文のみ）をキャンセルする
+    pub async fn cancel_all_orders(
+        &self,
+        address: H160,
+        token_manager: &TokenManager,
+        coin: Option<&str>,
+    ) -> Result<Vec<(OpenOrder, CancelResponseStatus)>, HttpClientError> {
+        let open_orders: Vec<OpenOrder> = self
+            .fetch_open_orders(address)
+            .await?
+            .into_iter()
+            .filter(|order| coin.is_none_or(|coin| order.coin == coin))
+            .collect();
+
+        if open_orders.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cancels = open_orders
+            .iter()
+            .map(|order| {
+                token_manager
+                    .get_index_by_internal_code(&order.coin)
+                    .map(|asset| CancelRequest::new(asset, order.order_id))
+                    .ok_or_else(|| {
+                        HttpClientError::InvalidInput(format!("Unknown coin: {}", order.coin))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let statuses = self.cancel_order(cancels).await?;
+        if open_orders.len() != statuses.len() {
+            return Err(HttpClientError::JsonParse(format!(
+                "Orders and cancel statuses length mismatch: {} != {}",
+                open_orders.len(),
+                statuses.len()
+            )));
+        }
+        Ok(open_orders.into_iter().zip(statuses).collect())
     }
 
-    pub async fn modify_order(&self) {
-        todo!("modify_order");
+    pub async fn modify_order(
+        &self,
+        modify: ModifyRequest,
+    ) -> Result<OrderResponseStatus, HttpClientError> {
+        self.modify_orders(vec![modify])
+            .await?
+            .pop()
+            .ok_or_else(|| HttpClientError::JsonParse("Empty modify statuses".to_string()))
     }
 
-    pub async fn modify_orders(&self) {
-        todo!("modify_orders");
+    pub async fn modify_orders(
+        &self,
+        modifies: Vec<ModifyRequest>,
+    ) -> Result<Vec<OrderResponseStatus>, HttpClientError> {
+        let modifies = modifies
+            .iter()
+            .map(ModifyRequest::to_wire)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(HttpClientError::InvalidInput)?;
+
+        let response = self
+            .send_exchange_request(Action::BatchModify(BulkModify { modifies }))
+            .await?;
+        let statuses: OrderStatuses = parse_response_data(response)?;
+        Ok(statuses.statuses)
     }
 
-    pub async fn update_leverage(&self) {
-        todo!("update_leverage");
+    /// レバレッジを変更する（is_cross = falseの場合はisolated）
+    pub async fn update_leverage(
+        &self,
+        asset: u32,
+        leverage: u32,
+        is_cross: bool,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let perp_meta = self.fetch_perp_meta().await?;
+        let universe = perp_meta.universe.get(asset as usize).ok_or_else(|| {
+            HttpClientError::InvalidInput(format!("Unknown perp asset: {}", asset))
+        })?;
+
+        if leverage == 0 || leverage > universe.max_leverage as u32 {
+            return Err(HttpClientError::InvalidInput(format!(
+                "Leverage for {} must be between 1 and {}: {}",
+                universe.name, universe.max_leverage, leverage
+            )));
+        }
+
+        self.send_exchange_request(Action::UpdateLeverage(UpdateLeverage {
+            asset,
+            is_cross,
+            leverage,
+        }))
+        .await
     }
 
-    pub async fn update_isolated_margin(&self) {
-        todo!("update_isolated_margin");
+    /// isolatedポジションの証拠金を追加（amount_usd > 0）または削除（amount_usd < 0）する
+    pub async fn update_isolated_margin(
+        &self,
+        asset: u32,
+        is_buy: bool,
+        amount_usd: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        if amount_usd == 0.0 {
+            return Err(HttpClientError::InvalidInput(
+                "Amount must not be zero".to_string(),
+            ));
+        }
+        let ntli = float_to_int(amount_usd, 6).map_err(HttpClientError::InvalidInput)?;
+
+        self.send_exchange_request(Action::UpdateIsolatedMargin(UpdateIsolatedMargin {
+            asset,
+            is_buy,
+            ntli,
+        }))
+        .await
     }
 
-    pub async fn transfer_usd(&self) {
-        todo!("transfer_usd");
+    /// Perp口座のUSDCを別のアドレスに送金する
+    pub async fn transfer_usd(
+        &self,
+        destination: H160,
+        amount: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let time = self.next_nonce();
+        let action = UsdSend {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            destination: format!("{:#x}", destination),
+            amount: amount_to_wire(amount, USD_DECIMALS)?,
+            time,
+        };
+        self.send_user_signed_request(action, time).await
     }
 
-    pub async fn transfer_spot_asset(&self) {
-        todo!("transfer_spot_asset");
+    /// Spotのトークンを別のアドレスに送金する
+    pub async fn transfer_spot_asset(
+        &self,
+        destination: H160,
+        token: &Token,
+        amount: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let time = self.next_nonce();
+        let action = SpotSend {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            destination: format!("{:#x}", destination),
+            token: format!("{}:{}", token.name, token.token_id),
+            amount: amount_to_wire(amount, token.wei_decimals)?,
+            time,
+        };
+        self.send_user_signed_request(action, time).await
     }
 
-    pub async fn initiate_withdrawal(&self) {
-        todo!("initiate_withdrawal");
+    /// USDCをブリッジ経由でArbitrumに出金する
+    pub async fn initiate_withdrawal(
+        &self,
+        destination: H160,
+        amount: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let time = self.next_nonce();
+        let action = Withdraw3 {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            destination: format!("{:#x}", destination),
+            amount: amount_to_wire(amount, USD_DECIMALS)?,
+            time,
+        };
+        self.send_user_signed_request(action, time).await
     }
 
-    pub async fn transfer_between_spot_and_perp(&self) {
-        todo!("transfer_spot_to_perp");
+    /// SpotとPerpの間でUSDCを移動する（to_perp = trueでSpot -> Perp）
+    pub async fn transfer_between_spot_and_perp(
+        &self,
+        amount: f64,
+        to_perp: bool,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let nonce = self.next_nonce();
+        let action = UsdClassTransfer {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            amount: amount_to_wire(amount, USD_DECIMALS)?,
+            to_perp,
+            nonce,
+        };
+        self.send_user_signed_request(action, nonce).await
     }
 
-    pub async fn deposit_or_withdraw_vault(&self) {
-        todo!("manage_vault_funds");
+    /// Vaultに入金（is_deposit = true）または出金する
+    pub async fn deposit_or_withdraw_vault(
+        &self,
+        vault_address: H160,
+        is_deposit: bool,
+        amount_usd: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let usd = usd_to_int(amount_usd)?;
+
+        self.send_exchange_request(Action::VaultTransfer(VaultTransfer {
+            vault_address: format!("{:#x}", vault_address),
+            is_deposit,
+            usd,
+        }))
+        .await
     }
 
-    pub async fn approve_api_wallet(&self) {
-        todo!("approve_api_wallet");
+    /// サブアカウントを作成してそのアドレスを返す
+    pub async fn create_sub_account(&self, name: &str) -> Result<H160, HttpClientError> {
+        let response = self
+            .send_exchange_request(Action::CreateSubAccount(CreateSubAccount {
+                name: name.to_string(),
+            }))
+            .await?;
+        parse_response_data(response)
     }
 
-    pub async fn approve_builder_fee(&self) {
-        todo!("approve_builder_fee");
+    /// サブアカウントにUSDCを入金（is_deposit = true）または出金する
+    pub async fn transfer_sub_account_usd(
+        &self,
+        sub_account: H160,
+        is_deposit: bool,
+        amount_usd: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let usd = usd_to_int(amount_usd)?;
+
+        self.send_exchange_request(Action::SubAccountTransfer(SubAccountTransfer {
+            sub_account_user: format!("{:#x}", sub_account),
+            is_deposit,
+            usd,
+        }))
+        .await
     }
 
-    pub async fn place_twap_order(&self) {
-        todo!("place_twap_order");
+    /// サブアカウントにSpotのトークンを入金（is_deposit = true）または出金する
+    pub async fn transfer_sub_account_spot(
+        &self,
+        sub_account: H160,
+        is_deposit: bool,
+        token: &Token,
+        amount: f64,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        self.send_exchange_request(Action::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user: format!("{:#x}", sub_account),
+            is_deposit,
+            token: format!("{}:{}", token.name, token.token_id),
+            amount: amount_to_wire(amount, token.wei_decimals)?,
+        }))
+        .await
     }
 
-    pub async fn cancel_twap_order(&self) {
-        todo!("cancel_twap_order");
+    /// APIウォレット（エージェント）を承認する（マスターのウォレットで署名）
+    pub async fn approve_api_wallet(
+        &self,
+        agent_address: H160,
+        agent_name: Option<&str>,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let nonce = self.next_nonce();
+        let action = ApproveAgent {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            agent_address: format!("{:#x}", agent_address),
+            agent_name: agent_name.map(str::to_string),
+            nonce,
+        };
+        self.send_user_signed_request(action, nonce).await
+    }
+
+    /// builderが受け取れる最大手数料（0.1bps単位、例: 10 = 1bps = 0.01%）を承認する
+    pub async fn approve_builder_fee(
+        &self,
+        builder: H160,
+        max_fee_rate: u32,
+    ) -> Result<ExchangeResponseData, HttpClientError> {
+        let percent =
+            float_to_wire(max_fee_rate as f64 / 1000.0).map_err(HttpClientError::InvalidInput)?;
+
+        let nonce = self.next_nonce();
+        let action = ApproveBuilderFee {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(self.is_mainnet).to_string(),
+            max_fee_rate: format!("{}%", percent),
+            builder: format!("{:#x}", builder),
+            nonce,
+        };
+        self.send_user_signed_request(action, nonce).await
+    }
+
+    /// TWAP注文を発注してtwap_idを返す
+    pub async fn place_twap_order(&self, twap: TwapRequest) -> Result<u64, HttpClientError> {
+        let twap = twap.to_wire().map_err(HttpClientError::InvalidInput)?;
+        let response = self
+            .send_exchange_request(Action::TwapOrder(TwapOrder { twap }))
+            .await?;
+
+        let response: TwapOrderResponse = parse_response_data(response)?;
+        match response.status {
+            TwapOrderStatus::Running { twap_id } => Ok(twap_id),
+            TwapOrderStatus::Error(msg) => Err(HttpClientError::ExchangeError(msg)),
+        }
+    }
+
+    pub async fn cancel_twap_order(
+        &self,
+        asset: u32,
+        twap_id: u64,
+    ) -> Result<CancelResponseStatus, HttpClientError> {
+        let response = self
+            .send_exchange_request(Action::TwapCancel(TwapCancel { asset, twap_id }))
+            .await?;
+        let response: TwapCancelResponse = parse_response_data(response)?;
+        Ok(response.status)
+    }
+}
+
+// userFillsByTime の1回のレスポンスの最大件数
+const USER_FILLS_PAGE_LIMIT: usize = 2000;
+
+// Perp口座のUSDCの小数点以下の桁数
+const USD_DECIMALS: u8 = 6;
+
+// 送金額を検証して送信用の文字列に変換（正の値かつ小数点以下decimals桁まで）
+// 送金額の確認（NaNは比較が常にfalseになるため、有限かどうかも確認する）
+fn ensure_positive_amount(amount: f64) -> Result<(), HttpClientError> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(HttpClientError::InvalidInput(format!(
+            "Amount must be positive: {}",
+            amount
+        )));
+    }
+    Ok(())
+}
+
+// USDの金額を1e6倍した整数に変換（Vault・サブアカウントへの入出金用）
+fn usd_to_int(amount: f64) -> Result<u64, HttpClientError> {
+    ensure_positive_amount(amount)?;
+    let usd = float_to_int(amount, USD_DECIMALS as i32).map_err(HttpClientError::InvalidInput)?;
+    Ok(usd as u64)
+}
+
+fn amount_to_wire(amount: f64, decimals: u8) -> Result<String, HttpClientError> {
+    ensure_positive_amount(amount)?;
+
+    let wire = float_to_wire(amount).map_err(HttpClientError::InvalidInput)?;
+    let fraction_digits = wire
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    if fraction_digits > decimals as usize {
+        return Err(HttpClientError::InvalidInput(format!(
+            "Amount {} has more than {} decimals",
+            amount, decimals
+        )));
+    }
+    Ok(wire)
+}
+
+// /exchange のレスポンスの data 部分を型に変換
+fn parse_response_data<T: DeserializeOwned>(
+    response: ExchangeResponseData,
+) -> Result<T, HttpClientError> {
+    let data = response.data.ok_or_else(|| {
+        HttpClientError::JsonParse(format!(
+            "Missing data in {} response",
+            response.response_type
+        ))
+    })?;
+    serde_json::from_value(data).map_err(|e| HttpClientError::JsonParse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn fill(tid: u64, time: u64) -> serde_json::Value {
+        serde_json::json!({
+            "closedPnl": "0.0",
+            "coin": "ETH",
+            "crossed": true,
+            "dir": "Open Long",
+            "hash": "0x0",
+            "oid": tid,
+            "px": "2000.0",
+            "side": "B",
+            "startPosition": "0.0",
+            "sz": "0.1",
+            "time": time,
+            "fee": "0.01",
+            "feeToken": "USDC",
+            "tid": tid,
+        })
+    }
+
+    fn page(fills: impl Iterator<Item = (u64, u64)>) -> String {
+        serde_json::Value::Array(fills.map(|(tid, time)| fill(tid, time)).collect()).to_string()
+    }
+
+    fn client(server: &Server) -> HttpClient {
+        HttpClient {
+            base_url: server.url(),
+            ..HttpClient::new(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn user_fills_stream_paginates_and_drops_duplicates() {
+        let mut server = Server::new_async().await;
+        // 1ページ目: 2000件（時刻 1..=2000）
+        let first = server
+            .mock("POST", "/info")
+            .match_body(Matcher::PartialJson(serde_json::json!({"startTime": 0})))
+            .with_body(page((1..=2000).map(|i| (i, i))))
+            .expect(1)
+            .create_async()
+            .await;
+        // 2ページ目: 最大時刻から取り直すので tid 2000 が重複し、件数が少ないので最後のページ
+        let second = server
+            .mock("POST", "/info")
+            .match_body(Matcher::PartialJson(serde_json::json!({"startTime": 2000})))
+            .with_body(page((2000..=2010).map(|i| (i, i))))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let fills: Vec<UserFills> = client
+            .user_fills_by_time_stream(H160::zero(), 0, None, None)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let tids: Vec<u64> = fills.iter().map(|fill| fill.tid).collect();
+        assert_eq!(tids, (1..=2010).collect::<Vec<_>>());
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn user_fills_stream_reports_page_stuck_at_same_time() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/info")
+            .match_body(Matcher::PartialJson(serde_json::json!({"startTime": 5})))
+            .with_body(page((1..=2000).map(|i| (i, 5))))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let results: Vec<Result<UserFills, HttpClientError>> = client
+            .user_fills_by_time_stream(H160::zero(), 5, None, None)
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 2001);
+        assert!(results[..2000].iter().all(|result| result.is_ok()));
+        assert!(matches!(
+            results.last(),
+            Some(Err(HttpClientError::IncompleteData(_)))
+        ));
+        mock.assert_async().await;
     }
 }
@@ -1,4 +1,5 @@
 pub mod http;
 pub mod models;
+pub mod signer;
 pub mod utils;
 pub mod websocket;
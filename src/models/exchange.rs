@@ -0,0 +1,785 @@
+use crate::signer::ExchangeSignature;
+use crate::utils::data_conversion::{float_to_wire, parse_str_to_f64};
+use ethers::core::rand;
+use ethers::core::utils::keccak256;
+use ethers::types::H160;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Client order id（16バイトの16進数文字列、例: 0x1234567890abcdef1234567890abcdef）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cloid(u128);
+
+impl Cloid {
+    pub fn random() -> Self {
+        Cloid(rand::random())
+    }
+
+    pub fn from_u128(value: u128) -> Self {
+        Cloid(value)
+    }
+
+    /// 戦略ID・内部の注文キーと連番から決定的にcloidを生成する
+    /// （keccak256(key + 連番のビッグエンディアン) の先頭16バイト。Bot側の状態からcloidを再計算できる）
+    pub fn derive(key: &str, sequence: u64) -> Self {
+        let mut bytes = key.as_bytes().to_vec();
+        bytes.extend(sequence.to_be_bytes());
+        let hash = keccak256(bytes);
+
+        let mut head = [0u8; 16];
+        head.copy_from_slice(&hash[..16]);
+        Cloid(u128::from_be_bytes(head))
+    }
+
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for Cloid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:032x}", self.0)
+    }
+}
+
+impl FromStr for Cloid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix("0x")
+            .ok_or_else(|| format!("Cloid must start with 0x: {}", s))?;
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Cloid must be 16 bytes of hex: {}", s));
+        }
+        u128::from_str_radix(hex, 16)
+            .map(Cloid)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Serialize for Cloid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cloid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tif {
+    Gtc,
+    Ioc,
+    Alo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tpsl {
+    Tp,
+    Sl,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderType {
+    Limit {
+        tif: Tif,
+    },
+    Trigger {
+        trigger_price: f64,
+        is_market: bool,
+        tpsl: Tpsl,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderGrouping {
+    Na,
+    NormalTpsl,
+    PositionTpsl,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub asset: u32, // TokenManager::get_token_index
+    pub is_buy: bool,
+    pub limit_price: f64,
+    pub size: f64,
+    pub reduce_only: bool,
+    pub order_type: OrderType,
+    pub cloid: Option<Cloid>,
+}
+
+impl OrderRequest {
+    pub fn new(
+        asset: u32,
+        is_buy: bool,
+        limit_price: f64,
+        size: f64,
+        reduce_only: bool,
+        order_type: OrderType,
+    ) -> Self {
+        Self {
+            asset,
+            is_buy,
+            limit_price,
+            size,
+            reduce_only,
+            order_type,
+            cloid: None,
+        }
+    }
+
+    pub fn to_wire(&self) -> Result<OrderWire, String> {
+        let order_type = match self.order_type {
+            OrderType::Limit { tif } => OrderTypeWire::Limit(LimitWire { tif }),
+            OrderType::Trigger {
+                trigger_price,
+                is_market,
+                tpsl,
+            } => OrderTypeWire::Trigger(TriggerWire {
+                is_market,
+                trigger_px: float_to_wire(trigger_price)?,
+                tpsl,
+            }),
+        };
+
+        Ok(OrderWire {
+            asset: self.asset,
+            is_buy: self.is_buy,
+            limit_px: float_to_wire(self.limit_price)?,
+            size: float_to_wire(self.size)?,
+            reduce_only: self.reduce_only,
+            order_type,
+            cloid: self.cloid,
+        })
+    }
+}
+
+// 送信用のフォーマット（フィールドの順番は署名に影響する）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderWire {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "b")]
+    pub is_buy: bool,
+    #[serde(rename = "p")]
+    pub limit_px: String,
+    #[serde(rename = "s")]
+    pub size: String,
+    #[serde(rename = "r")]
+    pub reduce_only: bool,
+    #[serde(rename = "t")]
+    pub order_type: OrderTypeWire,
+    #[serde(rename = "c", skip_serializing_if = "Option::is_none")]
+    pub cloid: Option<Cloid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderTypeWire {
+    Limit(LimitWire),
+    Trigger(TriggerWire),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitWire {
+    pub tif: Tif,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerWire {
+    pub is_market: bool,
+    pub trigger_px: String,
+    pub tpsl: Tpsl,
+}
+
+/// 注文に付与するビルダーコード（feeの単位は0.1bps）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderInfo {
+    #[serde(rename = "b")]
+    pub builder: String,
+    #[serde(rename = "f")]
+    pub fee: u32,
+}
+
+impl BuilderInfo {
+    pub fn new(builder: H160, fee: u32) -> Self {
+        Self {
+            builder: format!("{:#x}", builder),
+            fee,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkOrder {
+    pub orders: Vec<OrderWire>,
+    pub grouping: OrderGrouping,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder: Option<BuilderInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelRequest {
+    pub asset: u32,
+    pub order_id: u64,
+}
+
+impl CancelRequest {
+    pub fn new(asset: u32, order_id: u64) -> Self {
+        Self { asset, order_id }
+    }
+
+    pub fn to_wire(&self) -> CancelWire {
+        CancelWire {
+            asset: self.asset,
+            order_id: self.order_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelByCloidRequest {
+    pub asset: u32,
+    pub cloid: Cloid,
+}
+
+impl CancelByCloidRequest {
+    pub fn new(asset: u32, cloid: Cloid) -> Self {
+        Self { asset, cloid }
+    }
+
+    pub fn to_wire(&self) -> CancelByCloidWire {
+        CancelByCloidWire {
+            asset: self.asset,
+            cloid: self.cloid,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelWire {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "o")]
+    pub order_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelByCloidWire {
+    pub asset: u32,
+    pub cloid: Cloid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkCancel {
+    pub cancels: Vec<CancelWire>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkCancelByCloid {
+    pub cancels: Vec<CancelByCloidWire>,
+}
+
+/// 注文の指定方法（oid または cloid）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OrderId {
+    Oid(u64),
+    Cloid(Cloid),
+}
+
+#[derive(Debug, Clone)]
+pub struct ModifyRequest {
+    pub id: OrderId,
+    pub order: OrderRequest,
+}
+
+impl ModifyRequest {
+    pub fn new(id: OrderId, order: OrderRequest) -> Self {
+        Self { id, order }
+    }
+
+    pub fn to_wire(&self) -> Result<ModifyWire, String> {
+        Ok(ModifyWire {
+            oid: self.id.clone(),
+            order: self.order.to_wire()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifyWire {
+    pub oid: OrderId,
+    pub order: OrderWire,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkModify {
+    pub modifies: Vec<ModifyWire>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLeverage {
+    pub asset: u32,
+    pub is_cross: bool,
+    pub leverage: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateIsolatedMargin {
+    pub asset: u32,
+    pub is_buy: bool,
+    pub ntli: i64, // USD * 1e6
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultTransfer {
+    pub vault_address: String,
+    pub is_deposit: bool,
+    pub usd: u64, // USD * 1e6
+}
+
+#[derive(Debug, Clone)]
+pub struct TwapRequest {
+    pub asset: u32,
+    pub is_buy: bool,
+    pub size: f64,
+    pub reduce_only: bool,
+    pub minutes: u32,
+    pub randomize: bool,
+}
+
+impl TwapRequest {
+    pub fn new(
+        asset: u32,
+        is_buy: bool,
+        size: f64,
+        reduce_only: bool,
+        minutes: u32,
+        randomize: bool,
+    ) -> Self {
+        Self {
+            asset,
+            is_buy,
+            size,
+            reduce_only,
+            minutes,
+            randomize,
+        }
+    }
+
+    pub fn to_wire(&self) -> Result<TwapWire, String> {
+        Ok(TwapWire {
+            asset: self.asset,
+            is_buy: self.is_buy,
+            size: float_to_wire(self.size)?,
+            reduce_only: self.reduce_only,
+            minutes: self.minutes,
+            randomize: self.randomize,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapWire {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "b")]
+    pub is_buy: bool,
+    #[serde(rename = "s")]
+    pub size: String,
+    #[serde(rename = "r")]
+    pub reduce_only: bool,
+    #[serde(rename = "m")]
+    pub minutes: u32,
+    #[serde(rename = "t")]
+    pub randomize: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapOrder {
+    pub twap: TwapWire,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapCancel {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "t")]
+    pub twap_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSubAccount {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountTransfer {
+    pub sub_account_user: String,
+    pub is_deposit: bool,
+    pub usd: u64, // USD * 1e6
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountSpotTransfer {
+    pub sub_account_user: String,
+    pub is_deposit: bool,
+    pub token: String, // name:tokenId
+    pub amount: String,
+}
+
+/// ユーザー署名アクション（HyperliquidSignTransaction ドメインの EIP-712 で署名）
+pub trait UserSignedAction: Serialize {
+    const PRIMARY_TYPE: &'static str;
+    const FIELDS: &'static [(&'static str, &'static str)];
+
+    fn into_action(self) -> Action;
+
+    // 署名するメッセージ（送信する内容と異なる場合のみ上書きする）
+    fn signing_message(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsdSend {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub amount: String,
+    pub time: u64,
+}
+
+impl UserSignedAction for UsdSend {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:UsdSend";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::UsdSend(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotSend {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub token: String, // name:tokenId
+    pub amount: String,
+    pub time: u64,
+}
+
+impl UserSignedAction for SpotSend {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:SpotSend";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("token", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::SpotSend(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdraw3 {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub amount: String,
+    pub time: u64,
+}
+
+impl UserSignedAction for Withdraw3 {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:Withdraw";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::Withdraw3(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsdClassTransfer {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub amount: String,
+    pub to_perp: bool,
+    pub nonce: u64,
+}
+
+impl UserSignedAction for UsdClassTransfer {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:UsdClassTransfer";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("amount", "string"),
+        ("toPerp", "bool"),
+        ("nonce", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::UsdClassTransfer(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveAgent {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub agent_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_name: Option<String>,
+    pub nonce: u64,
+}
+
+impl UserSignedAction for ApproveAgent {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:ApproveAgent";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("agentAddress", "address"),
+        ("agentName", "string"),
+        ("nonce", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::ApproveAgent(self)
+    }
+
+    // 名前なしの場合、agentNameは送信しないが空文字として署名する
+    fn signing_message(&self) -> Result<Value, serde_json::Error> {
+        let mut message = serde_json::to_value(self)?;
+        if let Some(object) = message.as_object_mut() {
+            object
+                .entry("agentName")
+                .or_insert_with(|| Value::String(String::new()));
+        }
+        Ok(message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveBuilderFee {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub max_fee_rate: String, // "0.001%"など
+    pub builder: String,
+    pub nonce: u64,
+}
+
+impl UserSignedAction for ApproveBuilderFee {
+    const PRIMARY_TYPE: &'static str = "HyperliquidTransaction:ApproveBuilderFee";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("hyperliquidChain", "string"),
+        ("maxFeeRate", "string"),
+        ("builder", "address"),
+        ("nonce", "uint64"),
+    ];
+
+    fn into_action(self) -> Action {
+        Action::ApproveBuilderFee(self)
+    }
+}
+
+/// `/exchange` に送信するアクション
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Action {
+    Order(BulkOrder),
+    Cancel(BulkCancel),
+    CancelByCloid(BulkCancelByCloid),
+    BatchModify(BulkModify),
+    UpdateLeverage(UpdateLeverage),
+    UpdateIsolatedMargin(UpdateIsolatedMargin),
+    VaultTransfer(VaultTransfer),
+    TwapOrder(TwapOrder),
+    TwapCancel(TwapCancel),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountTransfer(SubAccountTransfer),
+    SubAccountSpotTransfer(SubAccountSpotTransfer),
+    UsdSend(UsdSend),
+    SpotSend(SpotSend),
+    Withdraw3(Withdraw3),
+    UsdClassTransfer(UsdClassTransfer),
+    ApproveAgent(ApproveAgent),
+    ApproveBuilderFee(ApproveBuilderFee),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExchangeRequest<T: Serialize> {
+    pub action: T,
+    pub nonce: u64,
+    pub signature: ExchangeSignature,
+    #[serde(rename = "vaultAddress", skip_serializing_if = "Option::is_none")]
+    pub vault_address: Option<H160>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", content = "response", rename_all = "camelCase")]
+pub enum ExchangeResponse {
+    Ok(ExchangeResponseData),
+    Err(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExchangeResponseData {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderStatuses {
+    pub statuses: Vec<OrderResponseStatus>,
+}
+
+/// 注文ごとの結果（送信した順）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderResponseStatus {
+    Resting(RestingOrder),
+    Filled(FilledOrder),
+    Error(String),
+    WaitingForFill,
+    WaitingForTrigger,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestingOrder {
+    #[serde(rename = "oid")]
+    pub order_id: u64,
+    pub cloid: Option<Cloid>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilledOrder {
+    #[serde(rename = "oid")]
+    pub order_id: u64,
+    #[serde(rename = "totalSz", deserialize_with = "parse_str_to_f64")]
+    pub total_size: f64,
+    #[serde(rename = "avgPx", deserialize_with = "parse_str_to_f64")]
+    pub avg_price: f64,
+    pub cloid: Option<Cloid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CancelStatuses {
+    pub statuses: Vec<CancelResponseStatus>,
+}
+
+/// キャンセルごとの結果（送信した順）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CancelResponseStatus {
+    Success,
+    Error(String),
+}
+
+impl CancelResponseStatus {
+    pub fn is_success(&self) -> bool {
+        matches!(self, CancelResponseStatus::Success)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwapOrderResponse {
+    pub status: TwapOrderStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TwapOrderStatus {
+    Running {
+        #[serde(rename = "twapId")]
+        twap_id: u64,
+    },
+    Error(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwapCancelResponse {
+    pub status: CancelResponseStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOID: &str = "0x1234567890abcdef1234567890abcdef";
+
+    #[test]
+    fn cloid_rejects_invalid_strings() {
+        // 0xなし
+        assert!(Cloid::from_str("1234567890abcdef1234567890abcdef").is_err());
+        // 長さが違う
+        assert!(Cloid::from_str("0x1234567890abcdef").is_err());
+        assert!(Cloid::from_str("0x1234567890abcdef1234567890abcdef00").is_err());
+        // 16進数以外の文字
+        assert!(Cloid::from_str("0x1234567890abcdef1234567890abcdeg").is_err());
+    }
+
+    #[test]
+    fn cloid_round_trips_through_display_and_serde() {
+        let cloid = Cloid::from_str(CLOID).unwrap();
+        assert_eq!(cloid.to_string(), CLOID);
+
+        let json = serde_json::to_string(&cloid).unwrap();
+        assert_eq!(json, format!("\"{}\"", CLOID));
+        assert_eq!(serde_json::from_str::<Cloid>(&json).unwrap(), cloid);
+
+        // 先頭が0でも32桁で表示される
+        let small = Cloid::from_u128(1);
+        assert_eq!(Cloid::from_str(&small.to_string()).unwrap(), small);
+    }
+
+    #[test]
+    fn cloid_derive_is_deterministic() {
+        assert_eq!(
+            Cloid::derive("strategy-a", 1),
+            Cloid::derive("strategy-a", 1)
+        );
+        assert_ne!(
+            Cloid::derive("strategy-a", 1),
+            Cloid::derive("strategy-a", 2)
+        );
+        assert_ne!(
+            Cloid::derive("strategy-a", 1),
+            Cloid::derive("strategy-b", 1)
+        );
+    }
+}
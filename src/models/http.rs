@@ -1,4 +1,8 @@
-use crate::utils::data_conversion::{parse_str_to_f64, parse_str_to_option_f64};
+use crate::models::Cloid;
+use crate::utils::data_conversion::{
+    parse_str_to_f64, parse_str_to_option_f64, parse_str_to_option_vec_f64,
+};
+use crate::utils::time::current_epoch_millis;
 use ethers::types::H160;
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -52,7 +56,7 @@ pub struct PerpMetaResponse {
     pub universe: Vec<PerpUniverse>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct PerpUniverse {
     pub name: String,
     #[serde(rename = "szDecimals")]
@@ -61,6 +65,46 @@ pub struct PerpUniverse {
     pub max_leverage: u8,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct PerpAssetContext {
+    #[serde(rename = "dayNtlVlm", deserialize_with = "parse_str_to_f64")]
+    pub day_notional_volume: f64,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub funding: f64,
+    #[serde(
+        rename = "impactPxs",
+        deserialize_with = "parse_str_to_option_vec_f64",
+        default
+    )]
+    pub impact_prices: Option<Vec<f64>>, // [bid, ask]
+    #[serde(rename = "markPx", deserialize_with = "parse_str_to_f64")]
+    pub mark_price: f64,
+    #[serde(
+        rename = "midPx",
+        deserialize_with = "parse_str_to_option_f64",
+        default
+    )]
+    pub mid_price: Option<f64>,
+    #[serde(rename = "openInterest", deserialize_with = "parse_str_to_f64")]
+    pub open_interest: f64,
+    #[serde(rename = "oraclePx", deserialize_with = "parse_str_to_f64")]
+    pub oracle_price: f64,
+    #[serde(deserialize_with = "parse_str_to_option_f64", default)]
+    pub premium: Option<f64>,
+    #[serde(rename = "prevDayPx", deserialize_with = "parse_str_to_f64")]
+    pub prev_day_price: f64,
+}
+
+// metaAndAssetCtxs のレスポンス: [meta, assetCtxs]
+pub type PerpAssetContextsResponse = (PerpMetaResponse, Vec<PerpAssetContext>);
+
+/// メタデータとその時点のコンテキストをまとめたもの
+#[derive(Debug, Clone)]
+pub struct PerpAsset {
+    pub universe: PerpUniverse,
+    pub context: PerpAssetContext,
+}
+
 #[derive(Deserialize)]
 pub struct SpotAssetResponse {
     pub tokens: Vec<Token>,
@@ -82,7 +126,6 @@ pub struct OpenOrder {
     pub timestamp: u64,
 }
 pub type OpenOrdersResponse = Vec<OpenOrder>;
-pub type HistoricalOrdersResponse = Vec<OpenOrder>;
 
 #[derive(Debug, Deserialize)]
 pub struct RateLimitResponse {
@@ -111,7 +154,250 @@ pub struct SpotTokenBalancesResponse {
     pub balances: Vec<SpotTokenBalance>,
 }
 
+// オークション価格の下限（HYPE）
+// 公式ドキュメント「HIP-1: Native token standard」のデプロイガスオークションの仕様
+// （31時間のダッチオークションで価格は500 HYPEまで線形に下がる）による
+pub const SPOT_DEPLOY_MIN_GAS: f64 = 500.0;
+
+/// Spotトークンデプロイのガスオークション（ダッチオークション）
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpotDeployAuction {
+    #[serde(rename = "startTimeSeconds")]
+    pub start_time_seconds: u64,
+    #[serde(rename = "durationSeconds")]
+    pub duration_seconds: u64,
+    #[serde(rename = "startGas", deserialize_with = "parse_str_to_f64")]
+    pub start_gas: f64,
+    #[serde(
+        rename = "currentGas",
+        deserialize_with = "parse_str_to_option_f64",
+        default
+    )]
+    pub current_gas: Option<f64>,
+    #[serde(
+        rename = "endGas",
+        deserialize_with = "parse_str_to_option_f64",
+        default
+    )]
+    pub end_gas: Option<f64>, // 落札された場合の価格
+}
+
+impl SpotDeployAuction {
+    /// 指定時刻（Unix秒）のオークション価格の見積もり
+    ///
+    /// start_gasからSPOT_DEPLOY_MIN_GASまで期間中に線形に下がるとして計算する。落札済みの場合はend_gasを返す。
+    /// 現在の価格はAPIの値を使う current_price() を利用すること。
+    pub fn price_at(&self, timestamp_seconds: u64) -> f64 {
+        if let Some(end_gas) = self.end_gas {
+            return end_gas;
+        }
+        if self.duration_seconds == 0 {
+            return self.start_gas;
+        }
+
+        let elapsed = timestamp_seconds
+            .saturating_sub(self.start_time_seconds)
+            .min(self.duration_seconds);
+        let progress = elapsed as f64 / self.duration_seconds as f64;
+        let floor = SPOT_DEPLOY_MIN_GAS.min(self.start_gas);
+        self.start_gas - (self.start_gas - floor) * progress
+    }
+
+    /// 現在のオークション価格（APIが返すcurrent_gasを優先し、ない場合のみ見積もる）
+    pub fn current_price(&self) -> f64 {
+        self.current_gas
+            .unwrap_or_else(|| self.price_at(current_epoch_millis() / 1000))
+    }
+
+    pub fn end_time_seconds(&self) -> u64 {
+        self.start_time_seconds + self.duration_seconds
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpotDeployStateResponse {
+    #[serde(rename = "gasAuction")]
+    pub gas_auction: SpotDeployAuction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarginSummary {
+    #[serde(rename = "accountValue", deserialize_with = "parse_str_to_f64")]
+    pub account_value: f64,
+    #[serde(rename = "totalNtlPos", deserialize_with = "parse_str_to_f64")]
+    pub total_notional_position: f64,
+    #[serde(rename = "totalRawUsd", deserialize_with = "parse_str_to_f64")]
+    pub total_raw_usd: f64,
+    #[serde(rename = "totalMarginUsed", deserialize_with = "parse_str_to_f64")]
+    pub total_margin_used: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Leverage {
+    Cross {
+        value: u32,
+    },
+    Isolated {
+        value: u32,
+        #[serde(rename = "rawUsd", deserialize_with = "parse_str_to_f64")]
+        raw_usd: f64,
+    },
+}
+
+impl Leverage {
+    pub fn value(&self) -> u32 {
+        match self {
+            Leverage::Cross { value } | Leverage::Isolated { value, .. } => *value,
+        }
+    }
+
+    pub fn is_cross(&self) -> bool {
+        matches!(self, Leverage::Cross { .. })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CumulativeFunding {
+    #[serde(rename = "allTime", deserialize_with = "parse_str_to_f64")]
+    pub all_time: f64,
+    #[serde(rename = "sinceOpen", deserialize_with = "parse_str_to_f64")]
+    pub since_open: f64,
+    #[serde(rename = "sinceChange", deserialize_with = "parse_str_to_f64")]
+    pub since_change: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Position {
+    pub coin: String,
+    #[serde(rename = "szi", deserialize_with = "parse_str_to_f64")]
+    pub size: f64, // ロングは正、ショートは負
+    #[serde(
+        rename = "entryPx",
+        deserialize_with = "parse_str_to_option_f64",
+        default
+    )]
+    pub entry_price: Option<f64>,
+    pub leverage: Leverage,
+    #[serde(
+        rename = "liquidationPx",
+        deserialize_with = "parse_str_to_option_f64",
+        default
+    )]
+    pub liquidation_price: Option<f64>,
+    #[serde(rename = "positionValue", deserialize_with = "parse_str_to_f64")]
+    pub position_value: f64,
+    #[serde(rename = "unrealizedPnl", deserialize_with = "parse_str_to_f64")]
+    pub unrealized_pnl: f64,
+    #[serde(rename = "returnOnEquity", deserialize_with = "parse_str_to_f64")]
+    pub return_on_equity: f64,
+    #[serde(rename = "marginUsed", deserialize_with = "parse_str_to_f64")]
+    pub margin_used: f64,
+    #[serde(rename = "maxLeverage")]
+    pub max_leverage: u32,
+    #[serde(rename = "cumFunding")]
+    pub cumulative_funding: CumulativeFunding,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetPosition {
+    #[serde(rename = "type")]
+    pub position_type: String, // oneWay
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PerpAccountSummaryResponse {
+    #[serde(rename = "marginSummary")]
+    pub margin_summary: MarginSummary,
+    #[serde(rename = "crossMarginSummary")]
+    pub cross_margin_summary: MarginSummary,
+    #[serde(
+        rename = "crossMaintenanceMarginUsed",
+        deserialize_with = "parse_str_to_f64"
+    )]
+    pub cross_maintenance_margin_used: f64,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub withdrawable: f64,
+    #[serde(rename = "assetPositions")]
+    pub asset_positions: Vec<AssetPosition>,
+    #[serde(rename = "time")]
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundingDelta {
+    pub coin: String,
+    #[serde(rename = "fundingRate", deserialize_with = "parse_str_to_f64")]
+    pub funding_rate: f64,
+    #[serde(rename = "szi", deserialize_with = "parse_str_to_f64")]
+    pub size: f64,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub usdc: f64, // 受け取りは正、支払いは負
+    #[serde(rename = "nSamples", default)]
+    pub n_samples: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserFunding {
+    pub delta: FundingDelta,
+    pub hash: String,
+    #[serde(rename = "time")]
+    pub timestamp: u64,
+}
+pub type UserFundingResponse = Vec<UserFunding>;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundingRate {
+    pub coin: String,
+    #[serde(rename = "fundingRate", deserialize_with = "parse_str_to_f64")]
+    pub funding_rate: f64,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub premium: f64,
+    #[serde(rename = "time")]
+    pub timestamp: u64,
+}
+pub type FundingHistoryResponse = Vec<FundingRate>;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PredictedFundingRate {
+    #[serde(rename = "fundingRate", deserialize_with = "parse_str_to_f64")]
+    pub funding_rate: f64,
+    #[serde(rename = "nextFundingTime")]
+    pub next_funding_time: u64,
+}
+
+// predictedFundings のレスポンス: [[coin, [[venue, {fundingRate, nextFundingTime} | null], ...]], ...]
+pub type PredictedFundingsResponse = Vec<(String, Vec<(String, Option<PredictedFundingRate>)>)>;
+
+/// 取引所（HlPerp, BinPerp, BybitPerpなど）ごとの予測ファンディングレート
+#[derive(Debug, Clone)]
+pub struct VenueFundingRate {
+    pub venue: String,
+    pub funding_rate: f64,
+    pub next_funding_time: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PredictedFunding {
+    pub coin: String,
+    pub venues: Vec<VenueFundingRate>,
+}
+
 #[derive(Debug, Deserialize)]
+pub struct SubAccount {
+    pub name: String,
+    #[serde(rename = "subAccountUser")]
+    pub sub_account_user: H160,
+    pub master: H160,
+    #[serde(rename = "clearinghouseState")]
+    pub clearinghouse_state: PerpAccountSummaryResponse,
+    #[serde(rename = "spotState")]
+    pub spot_state: SpotTokenBalancesResponse,
+}
+pub type SubAccountsResponse = Vec<SubAccount>;
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct UserFills {
     #[serde(rename = "closedPnl", deserialize_with = "parse_str_to_f64")]
     pub closed_pnl: f64,
@@ -142,9 +428,19 @@ pub struct UserFills {
     )]
     pub builder_fee: Option<f64>,
     pub tid: u64,
+    #[serde(default)]
+    pub cloid: Option<Cloid>,
 }
 pub type UserFillsResponse = Vec<UserFills>;
 
+#[derive(Debug, Deserialize)]
+pub struct TwapSliceFill {
+    pub fill: UserFills,
+    #[serde(rename = "twapId")]
+    pub twap_id: u64,
+}
+pub type TwapSliceFillsResponse = Vec<TwapSliceFill>;
+
 #[derive(Serialize)]
 pub struct OrderStatusRequest {
     #[serde(rename = "type")]
@@ -153,11 +449,11 @@ pub struct OrderStatusRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub oid: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cloid: Option<String>,
+    pub cloid: Option<Cloid>,
 }
 
 impl OrderStatusRequest {
-    pub fn new(user: H160, oid: Option<u64>, cloid: Option<String>) -> Result<Self, String> {
+    pub fn new(user: H160, oid: Option<u64>, cloid: Option<Cloid>) -> Result<Self, String> {
         if oid.is_none() && cloid.is_none() {
             return Err("Either oid or cloid must be provided".to_string());
         }
@@ -179,7 +475,7 @@ pub struct OrderStatusResponse {
 #[derive(Debug, Deserialize)]
 pub struct OrderDetail {
     pub order: OrderInfo,
-    pub status: String, // "filled" | "open" | "canceled" | "triggered" | "rejected" | "marginCanceled"
+    pub status: OrderStatus,
     #[serde(rename = "statusTimestamp")]
     pub status_timestamp: u64,
 }
@@ -211,10 +507,224 @@ pub struct OrderInfo {
     pub order_type: String, //Market, Limit
     #[serde(rename = "origSz", deserialize_with = "parse_str_to_f64")]
     pub original_size: f64,
-    pub tif: String, //FrontendMarketなど
-    pub cloid: Option<String>,
+    #[serde(default)]
+    pub tif: Option<String>, //FrontendMarketなど（トリガー注文ではnull）
+    pub cloid: Option<Cloid>,
 }
 
+// frontendOpenOrders はトリガー条件・TP/SL・子注文などを含む詳細な注文情報を返す
+pub type FrontendOpenOrdersResponse = Vec<OrderInfo>;
+
+/// 注文のステータス（orderStatus / historicalOrders / WSのorderUpdatesで共通）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OrderStatus {
+    Open,
+    Filled,
+    Canceled,
+    Triggered,
+    Rejected,
+    MarginCanceled,
+    VaultWithdrawalCanceled,
+    OpenInterestCapCanceled,
+    SelfTradeCanceled,
+    ReduceOnlyCanceled,
+    SiblingFilledCanceled,
+    DelistedCanceled,
+    LiquidatedCanceled,
+    ScheduledCancel,
+    Unknown(String), // 未対応のステータス（API側で追加された場合など）
+}
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Canceled => "canceled",
+            OrderStatus::Triggered => "triggered",
+            OrderStatus::Rejected => "rejected",
+            OrderStatus::MarginCanceled => "marginCanceled",
+            OrderStatus::VaultWithdrawalCanceled => "vaultWithdrawalCanceled",
+            OrderStatus::OpenInterestCapCanceled => "openInterestCapCanceled",
+            OrderStatus::SelfTradeCanceled => "selfTradeCanceled",
+            OrderStatus::ReduceOnlyCanceled => "reduceOnlyCanceled",
+            OrderStatus::SiblingFilledCanceled => "siblingFilledCanceled",
+            OrderStatus::DelistedCanceled => "delistedCanceled",
+            OrderStatus::LiquidatedCanceled => "liquidatedCanceled",
+            OrderStatus::ScheduledCancel => "scheduledCancel",
+            OrderStatus::Unknown(status) => status,
+        }
+    }
+
+    /// 板に残っている（約定・キャンセルされうる）状態か
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::Open)
+    }
+
+    /// これ以上状態が変わらない終了状態か（triggeredは子注文として板に乗るため含めない）
+    pub fn is_terminal(&self) -> bool {
+        !matches!(
+            self,
+            OrderStatus::Open | OrderStatus::Triggered | OrderStatus::Unknown(_)
+        )
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Canceled
+                | OrderStatus::MarginCanceled
+                | OrderStatus::VaultWithdrawalCanceled
+                | OrderStatus::OpenInterestCapCanceled
+                | OrderStatus::SelfTradeCanceled
+                | OrderStatus::ReduceOnlyCanceled
+                | OrderStatus::SiblingFilledCanceled
+                | OrderStatus::DelistedCanceled
+                | OrderStatus::LiquidatedCanceled
+                | OrderStatus::ScheduledCancel
+        )
+    }
+}
+
+impl From<&str> for OrderStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "open" => OrderStatus::Open,
+            "filled" => OrderStatus::Filled,
+            "canceled" => OrderStatus::Canceled,
+            "triggered" => OrderStatus::Triggered,
+            "rejected" => OrderStatus::Rejected,
+            "marginCanceled" => OrderStatus::MarginCanceled,
+            "vaultWithdrawalCanceled" => OrderStatus::VaultWithdrawalCanceled,
+            "openInterestCapCanceled" => OrderStatus::OpenInterestCapCanceled,
+            "selfTradeCanceled" => OrderStatus::SelfTradeCanceled,
+            "reduceOnlyCanceled" => OrderStatus::ReduceOnlyCanceled,
+            "siblingFilledCanceled" => OrderStatus::SiblingFilledCanceled,
+            "delistedCanceled" => OrderStatus::DelistedCanceled,
+            "liquidatedCanceled" => OrderStatus::LiquidatedCanceled,
+            "scheduledCancel" => OrderStatus::ScheduledCancel,
+            other => OrderStatus::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for OrderStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let status = String::deserialize(deserializer)?;
+        Ok(OrderStatus::from(status.as_str()))
+    }
+}
+
+// historicalOrders の要素は orderStatus の order と同じ形式
+pub type HistoricalOrder = OrderDetail;
+pub type HistoricalOrdersResponse = Vec<HistoricalOrder>;
+
+/// historicalOrders の結果を絞り込む条件（Noneの項目は絞り込まない）
+#[derive(Debug, Clone, Default)]
+pub struct HistoricalOrderFilter {
+    pub coin: Option<String>,
+    pub statuses: Option<Vec<OrderStatus>>,
+    pub start_time: Option<u64>, // statusTimestamp >= start_time
+    pub end_time: Option<u64>,   // statusTimestamp <= end_time
+}
+
+impl HistoricalOrderFilter {
+    pub fn matches(&self, order: &HistoricalOrder) -> bool {
+        self.coin
+            .as_ref()
+            .is_none_or(|coin| &order.order.coin == coin)
+            && self
+                .statuses
+                .as_ref()
+                .is_none_or(|statuses| statuses.contains(&order.status))
+            && self
+                .start_time
+                .is_none_or(|start_time| order.status_timestamp >= start_time)
+            && self
+                .end_time
+                .is_none_or(|end_time| order.status_timestamp <= end_time)
+    }
+
+    pub fn apply(&self, orders: HistoricalOrdersResponse) -> HistoricalOrdersResponse {
+        orders
+            .into_iter()
+            .filter(|order| self.matches(order))
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VaultDetailsResponse {
+    pub name: String,
+    #[serde(rename = "vaultAddress")]
+    pub vault_address: H160,
+    pub leader: H160,
+    pub description: String,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub apr: f64,
+    #[serde(rename = "followerState")]
+    pub follower_state: Option<VaultFollower>,
+    #[serde(rename = "leaderFraction", deserialize_with = "parse_str_to_f64")]
+    pub leader_fraction: f64,
+    #[serde(rename = "leaderCommission", deserialize_with = "parse_str_to_f64")]
+    pub leader_commission: f64,
+    pub followers: Vec<VaultFollower>,
+    #[serde(rename = "maxDistributable", deserialize_with = "parse_str_to_f64")]
+    pub max_distributable: f64,
+    #[serde(rename = "maxWithdrawable", deserialize_with = "parse_str_to_f64")]
+    pub max_withdrawable: f64,
+    #[serde(rename = "isClosed")]
+    pub is_closed: bool,
+    #[serde(rename = "allowDeposits")]
+    pub allow_deposits: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VaultFollower {
+    pub user: String, // リーダーの場合は"Leader"
+    #[serde(rename = "vaultEquity", deserialize_with = "parse_str_to_f64")]
+    pub vault_equity: f64,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub pnl: f64,
+    #[serde(rename = "allTimePnl", deserialize_with = "parse_str_to_f64")]
+    pub all_time_pnl: f64,
+    #[serde(rename = "daysFollowing")]
+    pub days_following: u64,
+    #[serde(rename = "vaultEntryTime")]
+    pub vault_entry_time: u64,
+    #[serde(rename = "lockupUntil")]
+    pub lockup_until: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserVaultEquity {
+    #[serde(rename = "vaultAddress")]
+    pub vault_address: H160,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub equity: f64,
+    #[serde(rename = "lockedUntilTimestamp", default)]
+    pub locked_until_timestamp: Option<u64>,
+}
+pub type UserVaultEquitiesResponse = Vec<UserVaultEquity>;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct OrderLevel {
     #[serde(rename = "px", deserialize_with = "parse_str_to_f64")]
@@ -287,15 +797,6 @@ pub struct CandleRequestDetails {
     end_time: u64,
 }
 
-// 現在のエポックミリ秒を取得するヘルパー関数
-fn current_epoch_millis() -> u64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let now = SystemTime::now();
-    now.duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_millis() as u64
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Candle {
     #[serde(rename = "t")]
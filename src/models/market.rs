@@ -105,6 +105,17 @@ impl TokenManager {
         }
     }
 
+    // 内部コード（"ETH"や"@107"など）からアセットのインデックスを取得
+    pub fn get_index_by_internal_code(&self, internal_code: &str) -> Option<u32> {
+        if let Some(symbol) = self.perp_internal_to_symbol.get(internal_code) {
+            return self.perp_symbol_to_index.get(symbol).copied();
+        }
+        self.spot_internal_to_symbol
+            .get(internal_code)
+            .and_then(|symbol| self.spot_symbol_to_index.get(symbol))
+            .copied()
+    }
+
     pub fn get_available_symbols(&self, market_type: MarketType) -> Vec<String> {
         match market_type {
             MarketType::Spot => self.spot_symbol_to_internal.keys().cloned().collect(),
@@ -1,7 +1,9 @@
+pub mod exchange;
 pub mod http;
 pub mod market;
 pub mod websocket;
 
+pub use exchange::*;
 pub use http::*;
 pub use market::*;
 pub use websocket::*;
@@ -1,5 +1,8 @@
-use crate::models::TradeSide;
-use serde::{Deserialize, Serialize};
+use crate::models::{parse_trade_side, Candle, Cloid, OrderStatus, TradeSide, UserFills};
+use crate::utils::data_conversion::parse_str_to_f64;
+use ethers::types::H160;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TradeData {
@@ -27,3 +30,386 @@ pub struct WsBook {
     pub ask_levels: Vec<WsLevel>,
     pub timestamp: u64,
 }
+
+/// WebSocketの購読対象（`{"method": "subscribe", "subscription": ...}` の中身）
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Subscription {
+    AllMids,
+    #[serde(rename_all = "camelCase")]
+    L2Book {
+        coin: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        n_sig_figs: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mantissa: Option<u32>,
+    },
+    Trades {
+        coin: String,
+    },
+    Candle {
+        coin: String,
+        interval: String,
+    },
+    Bbo {
+        coin: String,
+    },
+    ActiveAssetCtx {
+        coin: String,
+    },
+    Notification {
+        user: H160,
+    },
+    WebData2 {
+        user: H160,
+    },
+    OrderUpdates {
+        user: H160,
+    },
+    UserEvents {
+        user: H160,
+    },
+    #[serde(rename_all = "camelCase")]
+    UserFills {
+        user: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        aggregate_by_time: Option<bool>,
+    },
+    UserFundings {
+        user: H160,
+    },
+    UserNonFundingLedgerUpdates {
+        user: H160,
+    },
+    UserTwapSliceFills {
+        user: H160,
+    },
+    UserTwapHistory {
+        user: H160,
+    },
+}
+
+impl Subscription {
+    pub fn l2_book(coin: &str) -> Self {
+        Subscription::L2Book {
+            coin: coin.to_string(),
+            n_sig_figs: None,
+            mantissa: None,
+        }
+    }
+
+    pub fn trades(coin: &str) -> Self {
+        Subscription::Trades {
+            coin: coin.to_string(),
+        }
+    }
+
+    pub fn candle(coin: &str, interval: &str) -> Self {
+        Subscription::Candle {
+            coin: coin.to_string(),
+            interval: interval.to_string(),
+        }
+    }
+
+    /// 銘柄単位の購読であればその銘柄（内部コード）
+    pub fn coin(&self) -> Option<&str> {
+        match self {
+            Subscription::L2Book { coin, .. }
+            | Subscription::Trades { coin }
+            | Subscription::Candle { coin, .. }
+            | Subscription::Bbo { coin }
+            | Subscription::ActiveAssetCtx { coin } => Some(coin),
+            _ => None,
+        }
+    }
+}
+
+/// WebSocketConnection::events() で配信されるイベント
+#[derive(Clone, Debug)]
+pub enum WsEvent {
+    Mids(HashMap<String, String>),
+    Trade(TradeData),
+    BookUpdate(WsBook),
+    Candle(Candle),
+    OrderUpdates(Vec<WsOrderUpdate>),
+    UserFills(WsUserFills),
+    UserFundings(WsUserFundings),
+    UserNonFundingLedgerUpdates(WsUserNonFundingLedgerUpdates),
+    UserEvent(WsUserEvent),
+    // 型を定義していないチャネル（bbo / activeAssetCtx / notification / webData2 / userTwap*）
+    Raw {
+        channel: String,
+        data: serde_json::Value,
+    },
+    PostResponse {
+        id: u64,
+        response: serde_json::Value,
+    },
+    Reconnected, // 再接続して再購読のメッセージを送信し終えた（サーバーの応答前。キャッシュの取り直しなどに使う）
+}
+
+// orderUpdates チャネル
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsBasicOrder {
+    pub coin: String,
+    #[serde(deserialize_with = "parse_trade_side")]
+    pub side: TradeSide,
+    #[serde(rename = "limitPx", deserialize_with = "parse_str_to_f64")]
+    pub limit_price: f64,
+    #[serde(rename = "sz", deserialize_with = "parse_str_to_f64")]
+    pub size: f64,
+    #[serde(rename = "oid")]
+    pub order_id: u64,
+    pub timestamp: u64,
+    #[serde(rename = "origSz", deserialize_with = "parse_str_to_f64")]
+    pub original_size: f64,
+    #[serde(default)]
+    pub cloid: Option<Cloid>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsOrderUpdate {
+    pub order: WsBasicOrder,
+    pub status: OrderStatus,
+    #[serde(rename = "statusTimestamp")]
+    pub status_timestamp: u64,
+}
+
+// userFills チャネル（購読直後は isSnapshot: true で過去分が届く）
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsUserFills {
+    #[serde(rename = "isSnapshot", default)]
+    pub is_snapshot: bool,
+    pub user: H160,
+    pub fills: Vec<UserFills>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsUserFunding {
+    #[serde(rename = "time")]
+    pub timestamp: u64,
+    pub coin: String,
+    #[serde(deserialize_with = "parse_str_to_f64")]
+    pub usdc: f64, // 受け取りは正、支払いは負
+    #[serde(rename = "szi", deserialize_with = "parse_str_to_f64")]
+    pub size: f64,
+    #[serde(rename = "fundingRate", deserialize_with = "parse_str_to_f64")]
+    pub funding_rate: f64,
+}
+
+// userFundings チャネル
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsUserFundings {
+    #[serde(rename = "isSnapshot", default)]
+    pub is_snapshot: bool,
+    pub user: H160,
+    pub fundings: Vec<WsUserFunding>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsLedgerUpdate {
+    #[serde(rename = "time")]
+    pub timestamp: u64,
+    pub hash: String,
+    #[serde(deserialize_with = "parse_ledger_delta")]
+    pub delta: WsLedgerDelta,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LiquidatedPosition {
+    pub coin: String,
+    #[serde(rename = "szi", deserialize_with = "parse_str_to_f64")]
+    pub size: f64,
+}
+
+/// 資金移動の内容（金額はUSDC、spotTransferなどはトークンの数量）
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WsLedgerDelta {
+    Deposit {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+    },
+    Withdraw {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+        #[serde(default)]
+        nonce: u64,
+        #[serde(deserialize_with = "parse_str_to_f64", default)]
+        fee: f64,
+    },
+    InternalTransfer {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+        user: String,
+        destination: String,
+        #[serde(deserialize_with = "parse_str_to_f64", default)]
+        fee: f64,
+    },
+    SubAccountTransfer {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+        user: String,
+        destination: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    LedgerLiquidation {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        account_value: f64,
+        leverage_type: String,
+        liquidated_positions: Vec<LiquidatedPosition>,
+    },
+    VaultCreate {
+        vault: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+        #[serde(deserialize_with = "parse_str_to_f64", default)]
+        fee: f64,
+    },
+    VaultDeposit {
+        vault: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+    },
+    VaultDistribution {
+        vault: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    VaultWithdraw {
+        vault: String,
+        user: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        requested_usd: f64,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        commission: f64,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        closing_cost: f64,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        basis: f64,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        net_withdrawn_usd: f64,
+    },
+    VaultLeaderCommission {
+        user: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    SpotTransfer {
+        token: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        amount: f64,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc_value: f64,
+        user: String,
+        destination: String,
+        #[serde(deserialize_with = "parse_str_to_f64", default)]
+        fee: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    AccountClassTransfer {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        usdc: f64,
+        to_perp: bool,
+    },
+    SpotGenesis {
+        token: String,
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        amount: f64,
+    },
+    RewardsClaim {
+        #[serde(deserialize_with = "parse_str_to_f64")]
+        amount: f64,
+    },
+    // 未対応の種類（API側で追加された場合など）。typeと元のJSONをそのまま保持する
+    #[serde(skip_deserializing)]
+    Unknown {
+        delta_type: String,
+        data: serde_json::Value,
+    },
+}
+
+impl WsLedgerDelta {
+    /// delta の種類（"deposit", "withdraw" など）
+    pub fn delta_type(&self) -> &str {
+        match self {
+            WsLedgerDelta::Deposit { .. } => "deposit",
+            WsLedgerDelta::Withdraw { .. } => "withdraw",
+            WsLedgerDelta::InternalTransfer { .. } => "internalTransfer",
+            WsLedgerDelta::SubAccountTransfer { .. } => "subAccountTransfer",
+            WsLedgerDelta::LedgerLiquidation { .. } => "ledgerLiquidation",
+            WsLedgerDelta::VaultCreate { .. } => "vaultCreate",
+            WsLedgerDelta::VaultDeposit { .. } => "vaultDeposit",
+            WsLedgerDelta::VaultDistribution { .. } => "vaultDistribution",
+            WsLedgerDelta::VaultWithdraw { .. } => "vaultWithdraw",
+            WsLedgerDelta::VaultLeaderCommission { .. } => "vaultLeaderCommission",
+            WsLedgerDelta::SpotTransfer { .. } => "spotTransfer",
+            WsLedgerDelta::AccountClassTransfer { .. } => "accountClassTransfer",
+            WsLedgerDelta::SpotGenesis { .. } => "spotGenesis",
+            WsLedgerDelta::RewardsClaim { .. } => "rewardsClaim",
+            WsLedgerDelta::Unknown { delta_type, .. } => delta_type,
+        }
+    }
+}
+
+// 既知の形式で読めないdeltaは、更新全体を失わないよう Unknown として保持する
+fn parse_ledger_delta<'de, D>(deserializer: D) -> Result<WsLedgerDelta, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let data = serde_json::Value::deserialize(deserializer)?;
+    Ok(
+        WsLedgerDelta::deserialize(&data).unwrap_or_else(|_| WsLedgerDelta::Unknown {
+            delta_type: data
+                .get("type")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            data,
+        }),
+    )
+}
+
+// userNonFundingLedgerUpdates チャネル
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsUserNonFundingLedgerUpdates {
+    #[serde(rename = "isSnapshot", default)]
+    pub is_snapshot: bool,
+    pub user: H160,
+    #[serde(rename = "nonFundingLedgerUpdates")]
+    pub updates: Vec<WsLedgerUpdate>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsLiquidation {
+    #[serde(rename = "lid")]
+    pub liquidation_id: u64,
+    pub liquidator: String,
+    pub liquidated_user: String,
+    #[serde(rename = "liquidated_ntl_pos", deserialize_with = "parse_str_to_f64")]
+    pub liquidated_notional_position: f64,
+    #[serde(
+        rename = "liquidated_account_value",
+        deserialize_with = "parse_str_to_f64"
+    )]
+    pub liquidated_account_value: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsNonUserCancel {
+    pub coin: String,
+    #[serde(rename = "oid")]
+    pub order_id: u64,
+}
+
+// userEvents を購読すると "user" チャネルで届く
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WsUserEvent {
+    Fills(Vec<UserFills>),
+    Funding(WsUserFunding),
+    Liquidation(WsLiquidation),
+    NonUserCancel(Vec<WsNonUserCancel>),
+}
@@ -0,0 +1,318 @@
+use ethers::core::utils::keccak256;
+use ethers::signers::LocalWallet;
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712, Eip712DomainType, TypedData};
+use ethers::types::{H160, H256, U256};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// L1アクション（phantom agent）の署名ドメインで使うchainId
+pub const L1_CHAIN_ID: u64 = 1337;
+
+/// ユーザー署名アクションの `signatureChainId`（Arbitrum Sepolia）
+pub const SIGNATURE_CHAIN_ID: u64 = 0x66eee;
+
+#[derive(Debug)]
+pub enum SignerError {
+    Msgpack(String),
+    Eip712(String),
+    Signing(String),
+}
+
+impl std::fmt::Display for SignerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerError::Msgpack(e) => write!(f, "Failed to serialize action: {}", e),
+            SignerError::Eip712(e) => write!(f, "Failed to encode typed data: {}", e),
+            SignerError::Signing(e) => write!(f, "Failed to sign: {}", e),
+        }
+    }
+}
+
+impl Error for SignerError {}
+
+/// `/exchange` に送信する署名の形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeSignature {
+    pub r: U256,
+    pub s: U256,
+    pub v: u64,
+}
+
+/// L1アクションの代わりに署名する phantom agent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agent {
+    pub source: String,
+    pub connection_id: H256,
+}
+
+impl Agent {
+    pub fn new(connection_id: H256, is_mainnet: bool) -> Self {
+        Self {
+            source: if is_mainnet { "a" } else { "b" }.to_string(),
+            connection_id,
+        }
+    }
+
+    pub fn typed_data(&self) -> TypedData {
+        let message = serde_json::json!({
+            "source": self.source,
+            "connectionId": self.connection_id,
+        });
+        typed_data(
+            l1_domain(),
+            "Agent",
+            &[("source", "string"), ("connectionId", "bytes32")],
+            message,
+        )
+    }
+}
+
+/// ユーザー署名アクションの `hyperliquidChain`（"Mainnet" または "Testnet"）
+pub fn hyperliquid_chain(is_mainnet: bool) -> &'static str {
+    if is_mainnet {
+        "Mainnet"
+    } else {
+        "Testnet"
+    }
+}
+
+fn l1_domain() -> EIP712Domain {
+    EIP712Domain {
+        name: Some("Exchange".to_string()),
+        version: Some("1".to_string()),
+        chain_id: Some(U256::from(L1_CHAIN_ID)),
+        verifying_contract: Some(H160::zero()),
+        salt: None,
+    }
+}
+
+fn user_signed_domain(chain_id: U256) -> EIP712Domain {
+    EIP712Domain {
+        name: Some("HyperliquidSignTransaction".to_string()),
+        version: Some("1".to_string()),
+        chain_id: Some(chain_id),
+        verifying_contract: Some(H160::zero()),
+        salt: None,
+    }
+}
+
+fn typed_data(
+    domain: EIP712Domain,
+    primary_type: &str,
+    fields: &[(&str, &str)],
+    message: Value,
+) -> TypedData {
+    let domain_fields = [
+        ("name", "string"),
+        ("version", "string"),
+        ("chainId", "uint256"),
+        ("verifyingContract", "address"),
+    ];
+    let to_types = |fields: &[(&str, &str)]| {
+        fields
+            .iter()
+            .map(|(name, r#type)| Eip712DomainType {
+                name: name.to_string(),
+                r#type: r#type.to_string(),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut types = BTreeMap::new();
+    types.insert("EIP712Domain".to_string(), to_types(&domain_fields));
+    types.insert(primary_type.to_string(), to_types(fields));
+
+    let message = match message {
+        Value::Object(map) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    };
+
+    TypedData {
+        domain,
+        types,
+        primary_type: primary_type.to_string(),
+        message,
+    }
+}
+
+/// L1アクションのハッシュ: msgpack(action) + nonce（ビッグエンディアン） + vaultアドレスの有無/バイト列
+pub fn action_hash<T: Serialize>(
+    action: &T,
+    vault_address: Option<H160>,
+    nonce: u64,
+) -> Result<H256, SignerError> {
+    let mut bytes =
+        rmp_serde::to_vec_named(action).map_err(|e| SignerError::Msgpack(e.to_string()))?;
+    bytes.extend(nonce.to_be_bytes());
+    match vault_address {
+        Some(vault_address) => {
+            bytes.push(1);
+            bytes.extend(vault_address.as_bytes());
+        }
+        None => bytes.push(0),
+    }
+    Ok(H256(keccak256(bytes)))
+}
+
+/// EIP-712 の typed data をウォレットで署名
+pub fn sign_typed_data(
+    wallet: &LocalWallet,
+    payload: &TypedData,
+) -> Result<ExchangeSignature, SignerError> {
+    let digest = payload
+        .encode_eip712()
+        .map_err(|e| SignerError::Eip712(e.to_string()))?;
+    let signature = wallet
+        .sign_hash(H256(digest))
+        .map_err(|e| SignerError::Signing(e.to_string()))?;
+
+    Ok(ExchangeSignature {
+        r: signature.r,
+        s: signature.s,
+        v: signature.v,
+    })
+}
+
+/// L1アクション（注文・キャンセル・レバレッジ変更など）を phantom agent 経由で署名
+pub fn sign_l1_action<T: Serialize>(
+    wallet: &LocalWallet,
+    action: &T,
+    vault_address: Option<H160>,
+    nonce: u64,
+    is_mainnet: bool,
+) -> Result<ExchangeSignature, SignerError> {
+    let connection_id = action_hash(action, vault_address, nonce)?;
+    debug!("Connection id: {:?}", connection_id);
+    sign_typed_data(wallet, &Agent::new(connection_id, is_mainnet).typed_data())
+}
+
+/// ユーザー署名アクション（送金・出金・承認など）を署名
+///
+/// `action` には `signatureChainId` と `fields` のすべての項目が含まれている必要がある
+pub fn sign_user_signed_action<T: Serialize>(
+    wallet: &LocalWallet,
+    action: &T,
+    primary_type: &str,
+    fields: &[(&str, &str)],
+) -> Result<ExchangeSignature, SignerError> {
+    let message = serde_json::to_value(action).map_err(|e| SignerError::Eip712(e.to_string()))?;
+    let chain_id = message
+        .get("signatureChainId")
+        .and_then(|v| v.as_str())
+        .and_then(|v| U256::from_str_radix(v.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| SignerError::Eip712("Missing signatureChainId".to_string()))?;
+
+    let payload = typed_data(user_signed_domain(chain_id), primary_type, fields, message);
+    sign_typed_data(wallet, &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Action, BulkOrder, OrderGrouping, OrderRequest, OrderType, Tif, UsdSend, UserSignedAction,
+    };
+    use std::str::FromStr;
+
+    // 公式Python SDKのテストと同じ鍵・ベクター
+    const PRIVATE_KEY: &str = "0123456789012345678901234567890123456789012345678901234567890123";
+
+    #[derive(Serialize)]
+    struct DummyAction {
+        #[serde(rename = "type")]
+        action_type: &'static str,
+        num: u64,
+    }
+
+    fn wallet() -> LocalWallet {
+        LocalWallet::from_str(PRIVATE_KEY).unwrap()
+    }
+
+    fn u256(hex: &str) -> U256 {
+        U256::from_str_radix(hex.trim_start_matches("0x"), 16).unwrap()
+    }
+
+    #[test]
+    fn order_action_hash_matches_sdk() {
+        let order = OrderRequest::new(
+            4,
+            true,
+            1670.1,
+            0.0147,
+            false,
+            OrderType::Limit { tif: Tif::Ioc },
+        );
+        let action = Action::Order(BulkOrder {
+            orders: vec![order.to_wire().unwrap()],
+            grouping: OrderGrouping::Na,
+            builder: None,
+        });
+
+        let connection_id = action_hash(&action, None, 1677777606040).unwrap();
+        assert_eq!(
+            format!("{:#x}", connection_id),
+            "0x0fcbeda5ae3c4950a548021552a4fea2226858c4453571bf3f24ba017eac2908"
+        );
+    }
+
+    #[test]
+    fn l1_action_signature_matches_sdk() {
+        let action = DummyAction {
+            action_type: "dummy",
+            num: 100_000_000_000,
+        };
+
+        let mainnet = sign_l1_action(&wallet(), &action, None, 0, true).unwrap();
+        assert_eq!(
+            mainnet.r,
+            u256("0x53749d5b30552aeb2fca34b530185976545bb22d0b3ce6f62e31be961a59298")
+        );
+        assert_eq!(
+            mainnet.s,
+            u256("0x755c40ba9bf05223521753995abb2f73ab3229be8ec921f350cb447e384d8ed8")
+        );
+        assert_eq!(mainnet.v, 27);
+
+        let testnet = sign_l1_action(&wallet(), &action, None, 0, false).unwrap();
+        assert_eq!(
+            testnet.r,
+            u256("0x542af61ef1f429707e3c76c5293c80d01f74ef853e34b76efffcb57e574f9510")
+        );
+        assert_eq!(
+            testnet.s,
+            u256("0x17b8b32f086e8cdede991f1e2c529f5dd5297cbe8128500e00cbaf766204a613")
+        );
+        assert_eq!(testnet.v, 28);
+    }
+
+    #[test]
+    fn usd_send_signature_matches_sdk() {
+        let action = UsdSend {
+            signature_chain_id: format!("{:#x}", SIGNATURE_CHAIN_ID),
+            hyperliquid_chain: hyperliquid_chain(false).to_string(),
+            destination: "0x5e9ee1089755c3435139848e47e6635505d5a13a".to_string(),
+            amount: "1".to_string(),
+            time: 1687816341423,
+        };
+
+        let signature = sign_user_signed_action(
+            &wallet(),
+            &action.signing_message().unwrap(),
+            UsdSend::PRIMARY_TYPE,
+            UsdSend::FIELDS,
+        )
+        .unwrap();
+        assert_eq!(
+            signature.r,
+            u256("0x637b37dd731507cdd24f46532ca8ba6eec616952c56218baeff04144e4a77073")
+        );
+        assert_eq!(
+            signature.s,
+            u256("0x11a6a24900e6e314136d2592e2f8d502cd89b7c15b198e1bee043c9589f9fad7")
+        );
+        assert_eq!(signature.v, 27);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod eip712;
+
+pub use eip712::*;
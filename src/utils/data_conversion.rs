@@ -40,3 +40,62 @@ where
         )),
     }
 }
+
+/// f64を送信用の文字列に変換する（小数点以下8桁まで、末尾の0は削除）
+pub fn float_to_wire(x: f64) -> Result<String, String> {
+    let rounded = format!("{:.8}", x);
+    let parsed = rounded.parse::<f64>().map_err(|e| e.to_string())?;
+    if (parsed - x).abs() >= 1e-12 {
+        return Err(format!("float_to_wire causes rounding: {}", x));
+    }
+
+    let trimmed = if rounded.contains('.') {
+        rounded.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        rounded.as_str()
+    };
+    if trimmed == "-0" {
+        Ok("0".to_string())
+    } else {
+        Ok(trimmed.to_string())
+    }
+}
+
+/// f64を10^power倍した整数に変換する（USDの場合はpower = 6）
+pub fn float_to_int(x: f64, power: i32) -> Result<i64, String> {
+    // NaNは比較が常にfalseになり丸めチェックを通ってしまうため先に弾く
+    if !x.is_finite() {
+        return Err(format!("float_to_int got a non-finite value: {}", x));
+    }
+    let with_decimals = x * 10f64.powi(power);
+    if with_decimals.abs() >= i64::MAX as f64 {
+        return Err(format!("float_to_int overflows i64: {}", x));
+    }
+    if (with_decimals.round() - with_decimals).abs() >= 1e-3 {
+        return Err(format!("float_to_int causes rounding: {}", x));
+    }
+    Ok(with_decimals.round() as i64)
+}
+
+pub fn parse_str_to_option_vec_f64<'de, D>(deserializer: D) -> Result<Option<Vec<f64>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<Vec<Value>> = Option::deserialize(deserializer)?;
+    match value {
+        Some(values) => values
+            .iter()
+            .map(|value| match value {
+                Value::String(s) => s.parse::<f64>().map_err(serde::de::Error::custom),
+                Value::Number(n) => n
+                    .as_f64()
+                    .ok_or_else(|| serde::de::Error::custom("Failed to convert number to f64")),
+                _ => Err(serde::de::Error::custom(
+                    "Expected a string or number for f64",
+                )),
+            })
+            .collect::<Result<Vec<f64>, D::Error>>()
+            .map(Some),
+        None => Ok(None),
+    }
+}
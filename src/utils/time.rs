@@ -1,4 +1,5 @@
 use chrono::{DateTime, Local, TimeZone, Utc};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Unix時間（ミリ秒）をJSTに変換して文字列で返す
 ///
@@ -22,3 +23,11 @@ pub fn unix_time_to_jst(unix_time_millis: u64) -> String {
         None => "Invalid Timestamp".to_string(), // 無効なタイムスタンプの場合
     }
 }
+
+/// 現在のUnix時間（ミリ秒）を返す
+pub fn current_epoch_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
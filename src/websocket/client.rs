@@ -1,21 +1,47 @@
-use crate::models::{Candle, TradeData, TradeSide, WsBook, WsLevel};
+use crate::models::{Candle, Subscription, TradeData, TradeSide, WsBook, WsEvent, WsLevel};
+use futures_util::stream::SplitSink;
 use futures_util::SinkExt;
 use futures_util::StreamExt;
 use log::{debug, error, info};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::net::TcpStream;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 use tokio::time::Duration;
 use tokio_tungstenite::tungstenite::protocol::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsSink = SplitSink<WsStream, Message>;
+
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+// 書き込みタスクへの送信コマンド
+enum WsCommand {
+    // subscribe / unsubscribe（再接続時は登録内容から作り直すため、未送信のものは捨てる）
+    Subscription(Message),
+    Send(Message),
+}
+
+impl WsCommand {
+    fn into_message(self) -> Message {
+        match self {
+            WsCommand::Subscription(message) | WsCommand::Send(message) => message,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct WebSocketConnection {
-    ws_stream: Arc<Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
+    // 接続直後のストリーム（receive_messages の開始時に取り出す）
+    ws_stream: Arc<Mutex<Option<WsStream>>>,
+    // 送信はすべてこのチャネル経由で書き込みタスクに渡す（読み込みを待たない）
+    outbound_tx: mpsc::UnboundedSender<WsCommand>,
+    outbound_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<WsCommand>>>>,
+    events: broadcast::Sender<WsEvent>,
     pub url: String,
-    pub subscriptions: Arc<Mutex<Vec<HashMap<String, String>>>>,
+    pub subscriptions: Arc<Mutex<HashSet<Subscription>>>,
     pub all_mids: Arc<Mutex<HashMap<String, String>>>,
     pub trades: Arc<Mutex<HashMap<String, Vec<TradeData>>>>,
     pub l2_books: Arc<Mutex<HashMap<String, WsBook>>>,
@@ -29,10 +55,16 @@ impl WebSocketConnection {
         let (ws_stream, _) = connect_async(url).await?;
         info!("WebSocket connection established to {}", url);
 
+        let (outbound_tx, outbound_rx) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
         Ok(WebSocketConnection {
-            ws_stream: Arc::new(Mutex::new(ws_stream)),
+            ws_stream: Arc::new(Mutex::new(Some(ws_stream))),
+            outbound_tx,
+            outbound_rx: Arc::new(Mutex::new(Some(outbound_rx))),
+            events,
             url: url.to_string(),
-            subscriptions: Arc::new(Mutex::new(Vec::new())),
+            subscriptions: Arc::new(Mutex::new(HashSet::new())),
             all_mids: Arc::new(Mutex::new(HashMap::new())),
             trades: Arc::new(Mutex::new(HashMap::new())),
             l2_books: Arc::new(Mutex::new(HashMap::new())),
@@ -72,129 +104,204 @@ impl WebSocketConnection {
     }
 
     pub async fn receive_messages(&self) {
+        let mut outbound_rx = match self.outbound_rx.lock().await.take() {
+            Some(outbound_rx) => outbound_rx,
+            None => {
+                error!("receive_messages is already running.");
+                return;
+            }
+        };
+        let mut ws_stream = self.ws_stream.lock().await.take();
+        let mut is_reconnect = false;
+
         loop {
-            let mut ws_stream = self.ws_stream.lock().await;
+            let stream = match ws_stream.take() {
+                Some(stream) => stream,
+                None => match connect_async(&self.url).await {
+                    Ok((new_ws_stream, _)) => {
+                        info!("WebSocket reconnected successfully.");
+                        new_ws_stream
+                    }
+                    Err(e) => {
+                        error!("WebSocket reconnection failed: {}", e);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                },
+            };
+
+            // 再接続後は登録内容からサブスクリプションを再送信してから送信待ちのメッセージを流す。
+            // 切断中のsubscribe/unsubscribeは登録内容に反映済みなので、キューに残った分は捨てる
+            // （subscribe/unsubscribeは登録内容のロック中にキューに積むため、ロック中に取り出せば漏れはない）
+            let initial_messages = if is_reconnect {
+                let subscriptions = self.subscriptions.lock().await;
+                let mut pending = Vec::new();
+                while let Ok(command) = outbound_rx.try_recv() {
+                    if let WsCommand::Send(message) = command {
+                        pending.push(message);
+                    }
+                }
+
+                subscriptions
+                    .iter()
+                    .map(|subscription| {
+                        Message::Text(subscription_message("subscribe", subscription).to_string())
+                    })
+                    .chain(pending)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let (sink, mut stream) = stream.split();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+            let (flushed_tx, flushed_rx) = oneshot::channel();
+            let writer = tokio::spawn(write_messages(
+                sink,
+                outbound_rx,
+                initial_messages,
+                flushed_tx,
+                shutdown_rx,
+            ));
+
+            // 再購読のメッセージを送り終えてから再接続を通知する（読み込みは止めない）
+            if is_reconnect {
+                let events = self.events.clone();
+                tokio::spawn(async move {
+                    if flushed_rx.await.is_ok() {
+                        let _ = events.send(WsEvent::Reconnected);
+                    }
+                });
+            }
 
-            while let Some(msg) = ws_stream.next().await {
+            while let Some(msg) = stream.next().await {
                 match msg {
                     Ok(Message::Text(text)) => {
                         if let Err(e) = self.process_message(&text).await {
                             error!("Message processing error: {}", e);
                         }
                     }
-                    Ok(Message::Close(_)) | Err(_) => {
-                        info!("Connection closed. Attempting to reconnect.");
-
-                        // 再接続処理
-                        match connect_async(&self.url).await {
-                            Ok((new_ws_stream, _)) => {
-                                *ws_stream = new_ws_stream;
-                                info!("WebSocket reconnected successfully.");
-
-                                // 再接続後にサブスクリプションを再送信
-                                let subscriptions = self.subscriptions.lock().await.clone();
-                                for subscription in subscriptions {
-                                    let subscription_type = match subscription.get("type") {
-                                        Some(t) => t.clone(),
-                                        None => {
-                                            error!(
-                                                "Subscription missing type field: {:?}",
-                                                subscription
-                                            );
-                                            continue;
-                                        }
-                                    };
-
-                                    if let Err(e) = self
-                                        .subscribe_with_strings(&subscription_type, subscription)
-                                        .await
-                                    {
-                                        error!("Failed to resubscribe: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("WebSocket reconnection failed: {}", e);
-                                tokio::time::sleep(Duration::from_secs(5)).await;
-                            }
-                        }
-
-                        break;
-                    }
+                    Ok(Message::Close(_)) | Err(_) => break,
                     _ => {}
                 }
             }
 
-            match connect_async(&self.url).await {
-                Ok((new_ws_stream, _)) => {
-                    *ws_stream = new_ws_stream;
-                    info!("WebSocket reconnected successfully.");
-                }
+            info!("Connection closed. Attempting to reconnect.");
+
+            // 書き込みタスクを止めて送信チャネルを次の接続に引き継ぐ
+            let _ = shutdown_tx.send(());
+            outbound_rx = match writer.await {
+                Ok(outbound_rx) => outbound_rx,
                 Err(e) => {
-                    error!("WebSocket reconnection failed: {}", e);
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    error!("WebSocket writer task failed: {}", e);
+                    return;
                 }
-            }
+            };
+            is_reconnect = true;
         }
     }
 
+    /// 受信データをイベントとして購読する（受信側が遅れて溢れた分は `Lagged` になる）
+    pub fn events(&self) -> broadcast::Receiver<WsEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: WsEvent) {
+        // 受信者がいない場合のエラーは無視
+        let _ = self.events.send(event);
+    }
+
+    fn send_message(&self, message: Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.send_command(WsCommand::Send(Message::Text(message.to_string())))
+    }
+
+    fn send_command(&self, command: WsCommand) -> Result<(), Box<dyn std::error::Error>> {
+        self.outbound_tx
+            .send(command)
+            .map_err(|_| "WebSocket writer is closed")?;
+        Ok(())
+    }
+
     pub async fn subscribe(
         &self,
-        subscription_type: &str,
-        params: HashMap<&str, &str>,
+        subscription: Subscription,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let params = params
-            .into_iter()
-            .map(|(k, v)| (k.to_string(), v.to_string()))
-            .collect();
-        self.subscribe_with_strings(subscription_type, params).await
+        let mut subscriptions = self.subscriptions.lock().await;
+        if subscriptions.contains(&subscription) {
+            return Err(format!("Already subscribed: {:?}", subscription).into());
+        }
+
+        let subscription_msg = subscription_message("subscribe", &subscription);
+        info!("Subscription message: {}", subscription_msg);
+        self.send_command(WsCommand::Subscription(Message::Text(
+            subscription_msg.to_string(),
+        )))?;
+
+        info!("Subscription sent for: {:?}", subscription);
+
+        // 再接続時に再購読できるよう、購読内容をそのまま保持
+        subscriptions.insert(subscription);
+
+        Ok(())
     }
 
-    pub async fn subscribe_with_strings(
+    pub async fn unsubscribe(
         &self,
-        subscription_type: &str,
-        params: HashMap<String, String>,
+        subscription: Subscription,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut subscription_msg = serde_json::json!({
-            "method": "subscribe",
-            "subscription": {
-                "type": subscription_type
-            }
-        });
-
-        // サブスクリプションメッセージを更新
-        if let Some(subscription_obj) = subscription_msg
-            .get_mut("subscription")
-            .and_then(|v| v.as_object_mut())
-        {
-            for (key, value) in &params {
-                subscription_obj.insert(
-                    key.to_string(),
-                    serde_json::Value::String(value.to_string()),
-                );
-            }
+        let mut subscriptions = self.subscriptions.lock().await;
+        if !subscriptions.contains(&subscription) {
+            return Err(format!("Not subscribed: {:?}", subscription).into());
         }
 
-        info!("Subscription message: {}", subscription_msg);
+        let unsubscription_msg = subscription_message("unsubscribe", &subscription);
+        info!("Unsubscription message: {}", unsubscription_msg);
+        self.send_command(WsCommand::Subscription(Message::Text(
+            unsubscription_msg.to_string(),
+        )))?;
 
-        // WebSocket ストリームをロックして送信
-        let mut ws_stream = self.ws_stream.lock().await;
-        ws_stream
-            .send(Message::Text(subscription_msg.to_string()))
-            .await?;
+        info!("Unsubscription sent for: {:?}", subscription);
 
-        info!("Subscription sent for: {}", subscription_type);
+        subscriptions.remove(&subscription);
+        self.clear_cached_state(&subscription, &subscriptions).await;
 
-        // サブスクリプションを保持
-        let mut subscriptions = self.subscriptions.lock().await;
-        subscriptions.push(
-            params
+        Ok(())
+    }
+
+    // 購読解除した銘柄のキャッシュを削除（同じ銘柄の別の購読が残っている場合は残す）
+    async fn clear_cached_state(
+        &self,
+        subscription: &Subscription,
+        remaining: &HashSet<Subscription>,
+    ) {
+        let still_subscribed = |matches: fn(&Subscription) -> bool| {
+            remaining
                 .iter()
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .collect::<HashMap<String, String>>(),
-        );
+                .any(|s| matches(s) && s.coin() == subscription.coin())
+        };
 
-        Ok(())
+        match subscription {
+            Subscription::AllMids => self.all_mids.lock().await.clear(),
+            Subscription::L2Book { coin, .. }
+                if !still_subscribed(|s| matches!(s, Subscription::L2Book { .. })) =>
+            {
+                self.l2_books.lock().await.remove(coin);
+            }
+            Subscription::Trades { coin } => {
+                self.trades.lock().await.remove(coin);
+            }
+            Subscription::Candle { coin, interval } => {
+                let mut candles = self.candles.lock().await;
+                if still_subscribed(|s| matches!(s, Subscription::Candle { .. })) {
+                    if let Some(coin_candles) = candles.get_mut(coin) {
+                        coin_candles.retain(|c| &c.interval != interval);
+                    }
+                } else {
+                    candles.remove(coin);
+                }
+            }
+            _ => {}
+        }
     }
 
     async fn process_message(&self, message: &str) -> Result<(), Box<dyn std::error::Error>> {
@@ -207,6 +314,20 @@ impl WebSocketConnection {
                 "l2Book" => self.update_l2_book(message).await?,
                 "candle" => self.update_candles(message).await?,
                 "pong" => info!("Received pong message: {}", message),
+                "post" => self.handle_post_response(&parsed),
+                "orderUpdates" => self.emit_user_data(&parsed, WsEvent::OrderUpdates),
+                "userFills" => self.emit_user_data(&parsed, WsEvent::UserFills),
+                "userFundings" => self.emit_user_data(&parsed, WsEvent::UserFundings),
+                "userNonFundingLedgerUpdates" => {
+                    self.emit_user_data(&parsed, WsEvent::UserNonFundingLedgerUpdates)
+                }
+                "user" => self.emit_user_data(&parsed, WsEvent::UserEvent),
+                "bbo" | "activeAssetCtx" | "activeSpotAssetCtx" | "notification" | "webData2"
+                | "userTwapSliceFills" | "userTwapHistory" => self.emit(WsEvent::Raw {
+                    channel: channel.to_string(),
+                    data: parsed.get("data").cloned().unwrap_or_default(),
+                }),
+                "subscriptionResponse" => debug!("Subscription response: {}", message),
                 _ => {
                     info!("Unknown channel: {}", channel);
                 }
@@ -215,11 +336,47 @@ impl WebSocketConnection {
         Ok(())
     }
 
+    // ユーザーチャネルはキャッシュせずイベントとしてのみ配信する
+    fn emit_user_data<T, F>(&self, parsed: &Value, to_event: F)
+    where
+        T: serde::de::DeserializeOwned,
+        F: FnOnce(T) -> WsEvent,
+    {
+        let Some(data) = parsed.get("data") else {
+            error!("No 'data' field in the message: {}", parsed);
+            return;
+        };
+
+        match T::deserialize(data) {
+            Ok(data) => self.emit(to_event(data)),
+            Err(e) => error!("Failed to parse user data: {}, {}", e, data),
+        }
+    }
+
+    fn handle_post_response(&self, parsed: &Value) {
+        let data = parsed.get("data");
+        match data.and_then(|v| v.get("id")).and_then(|v| v.as_u64()) {
+            Some(id) => self.emit(WsEvent::PostResponse {
+                id,
+                response: data
+                    .and_then(|v| v.get("response"))
+                    .cloned()
+                    .unwrap_or_default(),
+            }),
+            None => error!("Post response without id: {}", parsed),
+        }
+    }
+
     async fn update_all_mids(&self, message: &str) -> Result<(), Box<dyn std::error::Error>> {
         let parsed: Value = serde_json::from_str(message)?;
 
         if let Some(data) = parsed.get("data").and_then(|v| v.get("mids")) {
             if let Some(mids) = data.as_object() {
+                // 購読解除後に届いたデータはキャッシュしない（解除とのロック順をそろえる）
+                let subscriptions = self.subscriptions.lock().await;
+                if !subscriptions.contains(&Subscription::AllMids) {
+                    return Ok(());
+                }
                 let mut all_mids = self.all_mids.lock().await;
                 all_mids.clear();
                 for (key, value) in mids {
@@ -227,6 +384,7 @@ impl WebSocketConnection {
                         all_mids.insert(key.clone(), price.to_string());
                     }
                 }
+                self.emit(WsEvent::Mids(all_mids.clone()));
             }
         }
         Ok(())
@@ -236,6 +394,7 @@ impl WebSocketConnection {
         let parsed: Value = serde_json::from_str(message)?;
 
         if let Some(data) = parsed.get("data").and_then(|v| v.as_array()) {
+            let subscriptions = self.subscriptions.lock().await;
             let mut trades = self.trades.lock().await;
 
             for trade in data {
@@ -278,6 +437,10 @@ impl WebSocketConnection {
                                     .collect::<Vec<String>>()
                             }),
                     ) {
+                        if !subscriptions.contains(&Subscription::trades(&coin)) {
+                            debug!("Ignoring trade for unsubscribed coin: {}", coin);
+                            continue;
+                        }
                         if let Some(side) = TradeSide::from_code(side_raw) {
                             let trade_data = TradeData {
                                 coin,
@@ -293,7 +456,8 @@ impl WebSocketConnection {
                             let coin_trades = trades
                                 .entry(trade_data.coin.clone())
                                 .or_insert_with(Vec::new);
-                            coin_trades.push(trade_data);
+                            coin_trades.push(trade_data.clone());
+                            self.emit(WsEvent::Trade(trade_data));
 
                             if coin_trades.len() > self.max_trades {
                                 coin_trades.remove(0);
@@ -390,8 +554,18 @@ impl WebSocketConnection {
                 .unwrap_or_default(),
         };
 
+        let subscriptions = self.subscriptions.lock().await;
+        let is_subscribed = subscriptions
+            .iter()
+            .any(|s| matches!(s, Subscription::L2Book { coin: c, .. } if c == coin));
+        if !is_subscribed {
+            debug!("Ignoring l2Book for unsubscribed coin: {}", coin);
+            return Ok(());
+        }
+
         let mut l2_books = self.l2_books.lock().await;
-        l2_books.insert(coin.to_string(), book);
+        l2_books.insert(coin.to_string(), book.clone());
+        self.emit(WsEvent::BookUpdate(book));
 
         Ok(())
     }
@@ -401,6 +575,14 @@ impl WebSocketConnection {
 
         if let Some(data) = parsed.get("data") {
             if let Ok(candle_data) = serde_json::from_value::<Candle>(data.clone()) {
+                let subscriptions = self.subscriptions.lock().await;
+                let subscription = Subscription::candle(&candle_data.coin, &candle_data.interval);
+                if !subscriptions.contains(&subscription) {
+                    debug!("Ignoring candle for unsubscribed: {:?}", subscription);
+                    return Ok(());
+                }
+
+                self.emit(WsEvent::Candle(candle_data.clone()));
                 let mut candles = self.candles.lock().await;
 
                 let coin_candles = candles
@@ -436,15 +618,22 @@ impl WebSocketConnection {
         Ok(())
     }
 
+    /// `{"method": "post"}` でinfo/actionリクエストを送信（結果は WsEvent::PostResponse で返る）
+    pub fn post(&self, id: u64, request: Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.send_message(serde_json::json!({
+            "method": "post",
+            "id": id,
+            "request": request,
+        }))
+    }
+
     pub async fn start_ping_task(&self) {
         loop {
             tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
             info!("Attempting to send WebSocket Ping...");
 
-            let heartbeat_msg = serde_json::json!({ "method": "ping" }).to_string();
-            let mut ws_stream = self.ws_stream.lock().await;
-            match ws_stream.send(Message::Text(heartbeat_msg)).await {
-                Ok(_) => info!("Heartbeat (ping) sent."),
+            match self.send_message(serde_json::json!({ "method": "ping" })) {
+                Ok(_) => info!("Heartbeat (ping) queued."),
                 Err(e) => {
                     error!("Failed to send heartbeat: {}", e);
                     break;
@@ -453,3 +642,44 @@ impl WebSocketConnection {
         }
     }
 }
+
+// 送信専用タスク。停止時は送信チャネルを返して次の接続で再利用する
+async fn write_messages(
+    mut sink: WsSink,
+    mut outbound_rx: mpsc::UnboundedReceiver<WsCommand>,
+    initial_messages: Vec<Message>,
+    flushed_tx: oneshot::Sender<()>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) -> mpsc::UnboundedReceiver<WsCommand> {
+    for message in initial_messages {
+        if let Err(e) = sink.send(message).await {
+            error!("Failed to send WebSocket message: {}", e);
+            return outbound_rx;
+        }
+    }
+    let _ = flushed_tx.send(());
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx => break,
+            command = outbound_rx.recv() => match command {
+                Some(command) => {
+                    if let Err(e) = sink.send(command.into_message()).await {
+                        error!("Failed to send WebSocket message: {}", e);
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+
+    outbound_rx
+}
+
+fn subscription_message(method: &str, subscription: &Subscription) -> Value {
+    serde_json::json!({
+        "method": method,
+        "subscription": subscription,
+    })
+}